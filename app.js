@@ -5,7 +5,7 @@ const session = require('express-session');
 const bodyParser = require('body-parser');
 const seedrandom = require('seedrandom');
 const fs = require('fs');
-const seatFinder = require('./seat_finder_native/native');
+const seatFinder = require('./seat_finder_native');
 const defaultConfig = require('./defaultConfig');
 
 const app = express();
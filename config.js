@@ -6,7 +6,8 @@ module.exports = {
     "initialTemperature": 1200,
     "coolingRate": 0.999991,
     "earlyStop": true,
-    "parallelRuns": 6
+    "parallelRuns": 6,
+    "snapshotIntervalMs": 1000
   },
   "seating": {
     "defaultSeatRadius": 50,
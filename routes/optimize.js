@@ -2,7 +2,8 @@ const express = require('express');
 const multer = require('multer');
 const { SeatingArrangement } = require('../models/SeatingArrangement');
 const { parseExcelFile } = require('../utils/parseExcelFile');
-const seatFinder = require('../seat_finder_native/native');
+const seatFinder = require('../seat_finder_native');
+const config = require('../config');
 
 const router = express.Router();
 const upload = multer({ storage: multer.memoryStorage() });
@@ -13,7 +14,7 @@ router.get('/optimize', (req, res) => {
 });
 
 // POST /optimize: Run the parameter search.
-router.post('/optimize', upload.single('excelFile'), (req, res) => {
+router.post('/optimize', upload.single('excelFile'), async (req, res) => {
 	const numTables = parseInt(req.body.numTables);
 	const seatsPerTable = parseInt(req.body.seatsPerTable);
 	const bonusParameter = parseFloat(req.body.bonusParameter);
@@ -84,14 +85,19 @@ router.post('/optimize', upload.single('excelFile'), (req, res) => {
 	const coolingRateMax = parseFloat(req.body.coolingRateMax) || 0.99999;
 	const coolingRateStep = parseFloat(req.body.coolingRateStep) || 0.0;
 
+	// A name listed twice in the spreadsheet gets seated twice; every run would be rejected.
+	const problems = JSON.parse(seatFinder.validateSeating(JSON.stringify(seatingArrangement), JSON.stringify([]), JSON.stringify(studentsMap), bonusConfig));
+	if (problems.length) {
+		return res.send('Error: ' + problems.map((problem) => problem.message).join('; '));
+	}
+
 	let bestScore = -Infinity;
 	let bestParams = {};
 	let bestArrangement = null;
 
 	for (let initTemp = initialTempMin; initTemp <= initialTempMax; initTemp += initialTempStep || 1) {
 		for (let coolRate = coolingRateMin; coolRate <= coolingRateMax; coolRate += coolingRateStep || 0.00001) {
-			const resultJson = seatFinder.optimizeSeating(JSON.stringify(seatingArrangement), JSON.stringify([]), JSON.stringify(studentsMap), bonusParameter, bonusConfig, iterations, initTemp, coolRate, earlyStop);
-			let resultObj = JSON.parse(resultJson);
+			let resultObj = await seatFinder.optimizeSeatingAsync(JSON.stringify(seatingArrangement), JSON.stringify([]), JSON.stringify(studentsMap), bonusParameter, bonusConfig, iterations, initTemp, coolRate, earlyStop, config.optimization.parallelRuns);
 			let score = resultObj.bestScore;
 			if (score > bestScore) {
 				bestScore = score;
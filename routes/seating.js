@@ -3,7 +3,7 @@ const multer = require('multer');
 const { SeatingArrangement } = require('../models/SeatingArrangement');
 const { computeStatistics } = require('../utils/computeStatistics');
 const { parseExcelFile } = require('../utils/parseExcelFile');
-const seatFinder = require('../seat_finder_native/native');
+const seatFinder = require('../seat_finder_native');
 const config = require('../config');
 
 const router = express.Router();
@@ -62,6 +62,9 @@ router.post('/upload', upload.single('excelFile'), (req, res) => {
 	const seatingArrangement = SeatingArrangement.createEmpty(numTables, L, bonusConfig);
 	req.session.seatingArrangement = seatingArrangement;
 
+	// Data problems the teacher can fix before starting a long optimization.
+	const wishAnalysis = JSON.parse(seatFinder.analyzeWishes(JSON.stringify(studentsMap), JSON.stringify({ seatsPerTable, bonusConfig })));
+
 	res.render('seating', {
 		seatingArrangement, // The instance exposes its tables via seatingArrangement.tables
 		students,
@@ -72,15 +75,35 @@ router.post('/upload', upload.single('excelFile'), (req, res) => {
 		L,
 		layoutMode,
 		layoutRows,
-		layoutColumns
+		layoutColumns,
+		wishAnalysis
 	});
 });
 
-// GET /progress: Get optimization progress.
+// Native options for the optimizations started here: progress carries the best arrangement so far.
+// `disruption` ({ moveCost, maxMoves }) keeps a recalculation close to the current arrangement.
+function optimizeOptionsJson(disruption) {
+	const options = { progress: { snapshotIntervalMs: config.optimization.snapshotIntervalMs } };
+	if (disruption) options.disruption = disruption;
+	return JSON.stringify(options);
+}
+
+// The minimal-disruption settings of a /recalculate request, or null if it asks for none.
+function disruptionFromBody(body) {
+	const moveCost = Number(body && body.moveCost) || 0;
+	const maxMoves = body && Number.isInteger(body.maxMoves) && body.maxMoves >= 0 ? body.maxMoves : null;
+	if (moveCost <= 0 && maxMoves === null) return null;
+	return { moveCost: Math.max(0, moveCost), maxMoves };
+}
+
+// GET /progress: Get progress of this session's optimization job.
 router.get('/progress', (req, res) => {
+	if (!req.session.jobId) {
+		return res.status(404).json({ error: 'No optimization running.' });
+	}
 	try {
 		// seatFinder.getProgress returns a JSON string.
-		const progressResult = seatFinder.getProgress();
+		const progressResult = seatFinder.getProgress(req.session.jobId);
 		const progress = JSON.parse(progressResult);
 		res.json(progress);
 	} catch (e) {
@@ -88,6 +111,18 @@ router.get('/progress', (req, res) => {
 	}
 });
 
+// POST /cancel: Stop this session's optimization; /result then shows the best arrangement so far.
+router.post('/cancel', (req, res) => {
+	if (!req.session.jobId) {
+		return res.json({ cancelled: false });
+	}
+	try {
+		res.json({ cancelled: seatFinder.cancelOptimization(req.session.jobId) });
+	} catch (e) {
+		res.json({ cancelled: false });
+	}
+});
+
 // POST /arrange: Arrange seats.
 router.post('/arrange', (req, res) => {
 	const numTables = parseInt(req.body.numTables);
@@ -192,7 +227,12 @@ router.post('/arrange', (req, res) => {
 	const initialTemperature = config.optimization.initialTemperature || 1200.0;
 	const coolingRate = config.optimization.coolingRate || 0.999991;
 	const earlyStopFlag = config.optimization.earlyStop !== undefined ? config.optimization.earlyStop : true;
-	seatFinder.optimizeSeating(JSON.stringify(req.session.seatingArrangement), JSON.stringify(fixedCoords), JSON.stringify(studentsMap), bonusParameter, bonusConfig, iterations, initialTemperature, coolingRate, earlyStopFlag, config.optimization.parallelRuns);
+	// Typos in hand-assigned seats would otherwise only show up as missing points in the result.
+	const problems = JSON.parse(seatFinder.validateSeating(JSON.stringify(req.session.seatingArrangement), JSON.stringify(fixedCoords), JSON.stringify(studentsMap), bonusConfig));
+	if (problems.length) {
+		return res.send('Error: ' + problems.map((problem) => problem.message).join('; '));
+	}
+	req.session.jobId = seatFinder.optimizeSeating(JSON.stringify(req.session.seatingArrangement), JSON.stringify(fixedCoords), JSON.stringify(studentsMap), bonusParameter, bonusConfig, iterations, initialTemperature, coolingRate, earlyStopFlag, config.optimization.parallelRuns, optimizeOptionsJson());
 
 	// Render the "optimizing" view which displays a modal with progress.
 	res.render('optimizing', {
@@ -214,18 +254,21 @@ router.post('/arrange', (req, res) => {
 router.get('/result', (req, res) => {
 	const studentsMap = req.session.studentsMap || {};
     try {
-        const progressResult = seatFinder.getProgress();
-        const progress = JSON.parse(progressResult);
-        if (progress.final_result) {
+        // getResult returns null while the job is still running; collecting the result ends the job.
+        const resultJson = req.session.jobId ? seatFinder.getResult(req.session.jobId) : null;
+        if (resultJson) {
           // Parse the optimized result and update the session
-          let resultObj = JSON.parse(progress.final_result);
+          let resultObj = JSON.parse(resultJson);
           req.session.seatingArrangement = new SeatingArrangement(resultObj.seatingArrangement.tables);
+          req.session.convergenceTrace = resultObj.trace || null;
+          delete req.session.jobId;
         }
       } catch (e) {
         console.error("Error fetching final result:", e);
+        delete req.session.jobId;
       }
 	const optimizedArrangement = req.session.seatingArrangement;
-	const stats = computeStatistics(optimizedArrangement, studentsMap, req.session.bonusParameter, req.session.L, req.session.bonusConfig);
+	const stats = computeStatistics(optimizedArrangement, studentsMap, req.session.bonusParameter, req.session.bonusConfig);
 	res.render('result', {
 		seatingArrangement: optimizedArrangement,
 		numTables: req.session.numTables,
@@ -233,6 +276,7 @@ router.get('/result', (req, res) => {
 		bonusParameter: req.session.bonusParameter,
 		bonusConfig: req.session.bonusConfig,
 		stats,
+		trace: req.session.convergenceTrace || null,
 		L: req.session.L,
 		layoutMode: req.session.layoutMode,
 		layoutRows: req.session.layoutRows || null,
@@ -241,7 +285,8 @@ router.get('/result', (req, res) => {
 	});
 });
 
-// POST /recalculate: Re-run optimization asynchronously.
+// POST /recalculate: Re-run optimization asynchronously. The body may carry moveCost and maxMoves
+// to keep students near their current seats.
 router.post('/recalculate', (req, res) => {
     // Re-instantiate the seating arrangement if necessary.
     let seatingArrangementObj = req.session.seatingArrangement;
@@ -265,7 +310,7 @@ router.post('/recalculate', (req, res) => {
     const earlyStopFlag = config.optimization.earlyStop !== undefined ? config.optimization.earlyStop : true;
   
     // Start the native optimization asynchronously.
-    seatFinder.optimizeSeating(
+    req.session.jobId = seatFinder.optimizeSeating(
       JSON.stringify(seatingArrangement),
       JSON.stringify(fixedCoords),
       JSON.stringify(studentsMap),
@@ -275,7 +320,8 @@ router.post('/recalculate', (req, res) => {
       initialTemperature,
       coolingRate,
       earlyStopFlag,
-      config.optimization.parallelRuns
+      config.optimization.parallelRuns,
+      optimizeOptionsJson(disruptionFromBody(req.body))
     );
   
     // Render the "optimizing" view which shows the progress modal.
@@ -311,11 +357,36 @@ router.post('/swap', (req, res) => {
 	let seatingArrangement = new SeatingArrangement(seatingArrangementObj.tables);
 	// Use the class method to swap seats.
 	seatingArrangement.swapSeats(seat1, seat2);
-	let stats = computeStatistics(seatingArrangement, req.session.studentsMap, req.session.bonusParameter, req.session.L, req.session.bonusConfig);
+	let stats = computeStatistics(seatingArrangement, req.session.studentsMap, req.session.bonusParameter, req.session.bonusConfig);
 	req.session.seatingArrangement = seatingArrangement;
 	res.json({ seatingArrangement, stats });
 });
 
+// POST /swap/preview: Score a proposed swap (or move into an empty seat) without applying it.
+router.post('/swap/preview', (req, res) => {
+	const { seat1, seat2 } = req.body;
+	let seatingArrangementObj = req.session.seatingArrangement;
+	if (!seatingArrangementObj) {
+		return res.status(400).json({ error: 'No seating arrangement found.' });
+	}
+	if (!seat1 || !seat2) {
+		return res.status(400).json({ error: 'Swap coordinates are missing.' });
+	}
+	try {
+		const preview = seatFinder.evaluateMove(
+			JSON.stringify(new SeatingArrangement(seatingArrangementObj.tables)),
+			JSON.stringify({ table: seat1.table, section: seat1.section, index: seat1.index }),
+			JSON.stringify({ table: seat2.table, section: seat2.section, index: seat2.index }),
+			JSON.stringify(req.session.studentsMap),
+			req.session.bonusParameter,
+			req.session.bonusConfig
+		);
+		res.json(JSON.parse(preview));
+	} catch (err) {
+		res.status(400).json({ error: err.message });
+	}
+});
+
 // GET /loadArrangement: Load seating arrangement.
 router.get('/loadArrangement', (req, res) => {
 	res.render('loadArrangement');
@@ -0,0 +1,27 @@
+// Builds the addon with cargo and copies the library to native/index.node, where
+// require('./native') finds it. Pass --debug for an unoptimised build.
+const { execFileSync } = require('child_process');
+const fs = require('fs');
+const path = require('path');
+
+const nativeDir = path.join(__dirname, 'native');
+const args = ['build', '--message-format=json-render-diagnostics'];
+if (!process.argv.includes('--debug')) args.push('--release');
+
+const output = execFileSync('cargo', args, {
+	cwd: nativeDir,
+	encoding: 'utf8',
+	maxBuffer: 64 * 1024 * 1024,
+	stdio: ['ignore', 'pipe', 'inherit']
+});
+const artifact = output
+	.split('\n')
+	.filter((line) => line.startsWith('{'))
+	.map((line) => JSON.parse(line))
+	.find((message) => message.reason === 'compiler-artifact' && message.target.kind.includes('cdylib'));
+const library = artifact && artifact.filenames.find((file) => /\.(so|dylib|dll)$/.test(file));
+if (!library) {
+	console.error('cargo did not produce the seat_finder_native library');
+	process.exit(1);
+}
+fs.copyFileSync(library, path.join(nativeDir, 'index.node'));
@@ -1,3 +1,17 @@
-var addon = require('../native');
+const addon = require('../native');
 
-console.log(addon.hello());
+// optimizeSeatingAsync(...optimizeSeating arguments, { onProgress, progressIntervalMs }). The
+// addon settles the Promise and calls onProgress itself; this only maps the trailing options
+// object to its arguments.
+function optimizeSeatingAsync(...args) {
+	let options = {};
+	if (args.length && typeof args[args.length - 1] === 'object' && args[args.length - 1] !== null) {
+		options = args.pop();
+	}
+	const { onProgress, progressIntervalMs } = options;
+	// The options JSON string is optional; keep onProgress at its fixed position after it.
+	while (args.length < 11) args.push(undefined);
+	return addon.optimizeSeatingAsync(...args, onProgress, progressIntervalMs);
+}
+
+module.exports = { ...addon, optimizeSeatingAsync };
@@ -0,0 +1,216 @@
+use crate::objective::{Objective, Ranked};
+use crate::Student;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// --- Acceptance Rules ---
+//
+// Decides whether the annealing loop keeps a candidate swap. Metropolis is the classic annealing
+// rule and depends on the temperature schedule; the other rules ignore the temperature and only
+// need a single, less scale-sensitive parameter. Under the lexicographic objective the rules see
+// `Ranked::weighted` scores, so a move that loses a satisfied student is a large loss rather
+// than ruled out.
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AcceptanceRule {
+    /// Accept worse moves with probability exp(delta / temperature).
+    Metropolis,
+    /// Accept if no worse than the current score `lateAcceptanceLength` iterations ago.
+    LateAcceptance,
+    /// Accept moves that lose less than a threshold falling linearly from `initialThreshold` to 0.
+    ThresholdAccepting,
+    /// Accept anything above a water level rising linearly from the initial score to `targetScore`.
+    GreatDeluge,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcceptanceOptions {
+    pub rule: AcceptanceRule,
+    pub late_acceptance_length: usize,
+    pub initial_threshold: f64,
+    /// Defaults to an upper bound on the score: every student gets their best possible neighbours.
+    /// Under the lexicographic objective, every student with wishes counts as satisfied on top.
+    pub target_score: Option<f64>,
+}
+
+impl Default for AcceptanceOptions {
+    fn default() -> Self {
+        AcceptanceOptions {
+            rule: AcceptanceRule::Metropolis,
+            late_acceptance_length: 5_000,
+            initial_threshold: 3.0,
+            target_score: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AcceptanceStats {
+    /// Moves that did not lower the score (always accepted).
+    pub improving: usize,
+    pub accepted_worse: usize,
+    pub rejected: usize,
+}
+
+impl AcceptanceStats {
+    /// Share of evaluated moves that were accepted.
+    pub fn acceptance_ratio(&self) -> f64 {
+        let total = self.improving + self.accepted_worse + self.rejected;
+        if total == 0 { 0.0 } else { (self.improving + self.accepted_worse) as f64 / total as f64 }
+    }
+}
+
+pub struct Acceptor {
+    rule: AcceptanceRule,
+    iterations: usize,
+    history: Vec<f64>,
+    initial_threshold: f64,
+    level: f64,
+    rain_speed: f64,
+    satisfied_weight: f64,
+    pub stats: AcceptanceStats,
+}
+
+impl Acceptor {
+    pub fn new(
+        options: &AcceptanceOptions,
+        initial: Ranked,
+        iterations: usize,
+        students_map: &HashMap<String, Student>,
+        bonus_parameter: f64,
+        objective: Objective,
+    ) -> Self {
+        let satisfied_weight = objective.satisfied_weight(students_map, bonus_parameter);
+        let initial_score = initial.weighted(satisfied_weight);
+        let target = Ranked {
+            satisfied: students_map.values().filter(|s| !s.wishes.is_empty()).count() as i64,
+            score: options.target_score.unwrap_or_else(|| score_upper_bound(students_map, bonus_parameter, objective)),
+        }
+        .weighted(satisfied_weight);
+        Acceptor {
+            rule: options.rule,
+            iterations: iterations.max(1),
+            history: vec![initial_score; options.late_acceptance_length.max(1)],
+            initial_threshold: options.initial_threshold,
+            level: initial_score,
+            rain_speed: (target - initial_score).max(0.0) / iterations.max(1) as f64,
+            satisfied_weight,
+            stats: AcceptanceStats::default(),
+        }
+    }
+
+    /// Whether to keep a move from `current` that changes it by `delta`.
+    #[inline(always)]
+    pub fn accept<R: Rng>(&mut self, iteration: usize, current: Ranked, delta: Ranked, temperature: f64, rng: &mut R) -> bool {
+        let current_score = current.weighted(self.satisfied_weight);
+        let delta = delta.weighted(self.satisfied_weight);
+        let candidate_score = current_score + delta;
+        let accepted = match self.rule {
+            AcceptanceRule::Metropolis => delta >= 0.0 || rng.gen_bool((delta / temperature).exp().min(1.0)),
+            AcceptanceRule::LateAcceptance => {
+                let slot = iteration % self.history.len();
+                let accepted = delta >= 0.0 || candidate_score >= self.history[slot];
+                self.history[slot] = if accepted { candidate_score } else { current_score };
+                accepted
+            }
+            AcceptanceRule::ThresholdAccepting => {
+                let progress = iteration as f64 / self.iterations as f64;
+                delta >= 0.0 || -delta < self.initial_threshold * (1.0 - progress)
+            }
+            AcceptanceRule::GreatDeluge => {
+                self.level += self.rain_speed;
+                delta >= 0.0 || candidate_score >= self.level
+            }
+        };
+        if delta >= 0.0 {
+            self.stats.improving += 1;
+        } else if accepted {
+            self.stats.accepted_worse += 1;
+        } else {
+            self.stats.rejected += 1;
+        }
+        accepted
+    }
+
+    /// Records a move rejected before the rule saw it, e.g. for breaking the move cap, so that
+    /// the history and the water level stay in step with the iterations.
+    pub fn reject(&mut self, iteration: usize, current: Ranked) {
+        match self.rule {
+            AcceptanceRule::LateAcceptance => {
+                let slot = iteration % self.history.len();
+                self.history[slot] = current.weighted(self.satisfied_weight);
+            }
+            AcceptanceRule::GreatDeluge => self.level += self.rain_speed,
+            AcceptanceRule::Metropolis | AcceptanceRule::ThresholdAccepting => {}
+        }
+        self.stats.rejected += 1;
+    }
+}
+
+// Every student with their best possible neighbours, passed through the objective's utility like
+// every seat score.
+fn score_upper_bound(students_map: &HashMap<String, Student>, bonus_parameter: f64, objective: Objective) -> f64 {
+    students_map.values().map(|student| objective.best_student_score(student, bonus_parameter)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::students;
+    use rand::prelude::*;
+
+    fn score(score: f64) -> Ranked {
+        Ranked { satisfied: 0, score }
+    }
+
+    #[test]
+    fn great_deluge_target_follows_the_objective() {
+        let students_map = students(&[("A", &["B", "C", "D", "E", "F", "G"]), ("B", &["A"]), ("C", &[])]);
+        let options = AcceptanceOptions { rule: AcceptanceRule::GreatDeluge, ..AcceptanceOptions::default() };
+        // A can have 3 + 2 * 0.8 wishes met, B one, C none; the bonus parameter is 2.
+        let sum = Acceptor::new(&options, score(0.0), 100, &students_map, 2.0, Objective::Sum);
+        assert!((sum.rain_speed * 100.0 - (9.2 + 2.0)).abs() < 1e-9);
+        let sqrt = Acceptor::new(&options, score(0.0), 100, &students_map, 2.0, Objective::Sqrt);
+        assert!((sqrt.rain_speed * 100.0 - (9.2f64.sqrt() + 2.0f64.sqrt())).abs() < 1e-9);
+        // Lexicographic: A and B can both be satisfied, each worth A's best score of 9.2.
+        let lexicographic = Acceptor::new(&options, score(0.0), 100, &students_map, 2.0, Objective::Lexicographic);
+        assert!((lexicographic.rain_speed * 100.0 - (9.2 + 2.0 + 2.0 * 9.2)).abs() < 1e-9);
+        let targeted = AcceptanceOptions { target_score: Some(50.0), ..options };
+        assert!((Acceptor::new(&targeted, score(10.0), 100, &students_map, 2.0, Objective::Sqrt).rain_speed - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn losing_a_satisfied_student_is_priced_by_the_temperature() {
+        let students_map = students(&[("A", &["B"]), ("B", &["A"])]);
+        let mut acceptor =
+            Acceptor::new(&AcceptanceOptions::default(), Ranked { satisfied: 2, score: 2.0 }, 100, &students_map, 1.0, Objective::Lexicographic);
+        let current = Ranked { satisfied: 2, score: 2.0 };
+        // Gains 0.5 score but leaves a student with nothing, who is worth 1 (a single met wish).
+        let lose_one = Ranked { satisfied: -1, score: 0.5 };
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!((0..50).any(|i| acceptor.accept(i, current, lose_one, 100.0, &mut rng)));
+        assert!(!(0..50).any(|i| acceptor.accept(i, current, lose_one, 0.01, &mut rng)));
+        // Satisfying one more student outweighs a smaller loss in score.
+        assert!(acceptor.accept(0, current, Ranked { satisfied: 1, score: -0.5 }, 0.01, &mut rng));
+    }
+
+    #[test]
+    fn rejected_moves_keep_the_schedule_in_step() {
+        let students_map = students(&[("A", &["B"]), ("B", &["A"])]);
+        let deluge = AcceptanceOptions { rule: AcceptanceRule::GreatDeluge, target_score: Some(10.0), ..AcceptanceOptions::default() };
+        let mut acceptor = Acceptor::new(&deluge, score(0.0), 10, &students_map, 1.0, Objective::Sum);
+        for i in 0..5 {
+            acceptor.reject(i, score(0.0));
+        }
+        assert!((acceptor.level - 5.0).abs() < 1e-9);
+        assert_eq!(acceptor.stats.rejected, 5);
+
+        let late = AcceptanceOptions { rule: AcceptanceRule::LateAcceptance, late_acceptance_length: 2, ..AcceptanceOptions::default() };
+        let mut acceptor = Acceptor::new(&late, score(0.0), 10, &students_map, 1.0, Objective::Sum);
+        acceptor.reject(0, score(4.0));
+        assert_eq!(acceptor.history, vec![4.0, 0.0]);
+    }
+}
@@ -0,0 +1,106 @@
+use serde::Deserialize;
+use std::f64::consts::PI;
+
+// --- Cooling Schedules ---
+//
+// Temperature as a function of the iteration. Geometric cooling keeps the historical behaviour;
+// the others end at `finalTemperature`, which defaults to where geometric cooling with the
+// configured cooling rate would end, so switching schedules does not change the overall range.
+
+const MIN_TEMPERATURE: f64 = 1e-8;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CoolingSchedule {
+    /// T <- T * coolingRate.
+    Geometric,
+    /// Straight line from the initial to the final temperature.
+    Linear,
+    /// T = T0 / (1 + c * ln(1 + k)), with c chosen to reach the final temperature; cools fast at
+    /// first and very slowly afterwards.
+    Logarithmic,
+    /// T <- T / (1 + beta * T), with beta chosen to reach the final temperature.
+    LundyMees,
+    /// `cycles` cosine-shaped cool-downs, each restarting from the initial temperature.
+    CosineCyclic,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CoolingOptions {
+    pub schedule: CoolingSchedule,
+    pub final_temperature: Option<f64>,
+    pub cycles: usize,
+}
+
+impl Default for CoolingOptions {
+    fn default() -> Self {
+        CoolingOptions {
+            schedule: CoolingSchedule::Geometric,
+            final_temperature: None,
+            cycles: 4,
+        }
+    }
+}
+
+pub struct Cooler {
+    schedule: CoolingSchedule,
+    initial_temperature: f64,
+    final_temperature: f64,
+    cooling_rate: f64,
+    logarithmic_scale: f64,
+    lundy_mees_beta: f64,
+    cycle_length: usize,
+    iterations: usize,
+    iteration: usize,
+    temperature: f64,
+}
+
+impl Cooler {
+    pub fn new(options: &CoolingOptions, initial_temperature: f64, cooling_rate: f64, iterations: usize) -> Self {
+        let iterations = iterations.max(1);
+        let final_temperature = options
+            .final_temperature
+            .unwrap_or_else(|| initial_temperature * cooling_rate.powf(iterations as f64))
+            .max(MIN_TEMPERATURE);
+        Cooler {
+            schedule: options.schedule,
+            initial_temperature,
+            final_temperature,
+            cooling_rate,
+            logarithmic_scale: (initial_temperature / final_temperature - 1.0).max(0.0) / (1.0 + iterations as f64).ln(),
+            lundy_mees_beta: (1.0 / final_temperature - 1.0 / initial_temperature).max(0.0) / iterations as f64,
+            cycle_length: (iterations / options.cycles.max(1)).max(1),
+            iterations,
+            iteration: 0,
+            temperature: initial_temperature,
+        }
+    }
+
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    /// Advances one iteration and returns the new temperature.
+    #[inline(always)]
+    pub fn next(&mut self) -> f64 {
+        self.iteration += 1;
+        let (t0, tf) = (self.initial_temperature, self.final_temperature);
+        let temperature = match self.schedule {
+            CoolingSchedule::Geometric => self.temperature * self.cooling_rate,
+            CoolingSchedule::Linear => {
+                let progress = (self.iteration as f64 / self.iterations as f64).min(1.0);
+                t0 - (t0 - tf) * progress
+            }
+            CoolingSchedule::Logarithmic => t0 / (1.0 + self.logarithmic_scale * (1.0 + self.iteration as f64).ln()),
+            CoolingSchedule::LundyMees => self.temperature / (1.0 + self.lundy_mees_beta * self.temperature),
+            CoolingSchedule::CosineCyclic => {
+                // Runs from just above 0 to exactly 1 within each cycle, so every cycle ends at `tf`.
+                let position = ((self.iteration - 1) % self.cycle_length + 1) as f64 / self.cycle_length as f64;
+                tf + 0.5 * (t0 - tf) * (1.0 + (PI * position).cos())
+            }
+        };
+        self.temperature = temperature.max(MIN_TEMPERATURE);
+        self.temperature
+    }
+}
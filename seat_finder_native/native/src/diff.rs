@@ -0,0 +1,179 @@
+use crate::scoring::{AdjacencyKind, SeatGraph};
+use crate::{build_wishes_map, evaluate_seating, Coordinate, SeatingArrangement, Student};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+// --- Arrangement Diff ---
+//
+// Compares two arrangements of the same room: who moved, who changed table, which neighbour
+// pairs were gained or lost, and how the score changed. Pairs use the scoring neighbour model,
+// so a pair counts as neighbours if either student is scored against the other.
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Move {
+    pub name: String,
+    /// `None` if the student is not seated in that arrangement.
+    pub from: Option<Coordinate>,
+    pub to: Option<Coordinate>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeighbourPair {
+    pub students: [String; 2],
+    pub kind: AdjacencyKind,
+    /// At least one of the two wished for the other.
+    pub wished: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatingDiff {
+    pub moved: Vec<Move>,
+    pub changed_table: Vec<String>,
+    pub pairs_gained: Vec<NeighbourPair>,
+    pub pairs_lost: Vec<NeighbourPair>,
+    pub score_before: f64,
+    pub score_after: f64,
+    pub score_difference: f64,
+    /// Number of students seated differently; same as `moved.len()`.
+    pub distance: usize,
+}
+
+/// Whether both arrangements have the same tables and row lengths, so seats can be compared.
+pub fn same_shape(a: &SeatingArrangement, b: &SeatingArrangement) -> bool {
+    a.tables.len() == b.tables.len()
+        && a.tables.iter().zip(&b.tables).all(|(x, y)| x.top.len() == y.top.len() && x.bottom.len() == y.bottom.len())
+}
+
+pub fn diff_seating(
+    before: &SeatingArrangement,
+    after: &SeatingArrangement,
+    students_map: &HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+) -> SeatingDiff {
+    let graph = SeatGraph::new(before, bonus_config);
+    let wishes_map = build_wishes_map(students_map);
+    let seats_before = seats_by_name(&graph, before);
+    let seats_after = seats_by_name(&graph, after);
+
+    let mut moved = Vec::new();
+    let mut changed_table = Vec::new();
+    let mut names: Vec<&String> = seats_before.keys().chain(seats_after.keys()).copied().collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let (from, to) = (seats_before.get(name).copied(), seats_after.get(name).copied());
+        if from == to {
+            continue;
+        }
+        let table = |seat: Option<usize>| seat.map(|s| graph.coords[s].table);
+        if table(from) != table(to) {
+            changed_table.push(name.clone());
+        }
+        moved.push(Move {
+            name: name.clone(),
+            from: from.map(|s| graph.coords[s].clone()),
+            to: to.map(|s| graph.coords[s].clone()),
+        });
+    }
+
+    let pairs_before = neighbour_pairs(&graph, before);
+    let pairs_after = neighbour_pairs(&graph, after);
+    let wished = |(a, b): &(&String, &String)| {
+        let wishes = |x: &String, y: &String| wishes_map.get(x.as_str()).is_some_and(|w| w.contains(y.as_str()));
+        wishes(a, b) || wishes(b, a)
+    };
+    let only_in = |from: &BTreeMap<(&String, &String), AdjacencyKind>, other: &BTreeMap<(&String, &String), AdjacencyKind>| {
+        from.iter()
+            .filter(|(pair, _)| !other.contains_key(pair))
+            .map(|(pair, &kind)| NeighbourPair { students: [pair.0.clone(), pair.1.clone()], kind, wished: wished(pair) })
+            .collect::<Vec<_>>()
+    };
+
+    let score_before = evaluate_seating(before, students_map, &wishes_map, bonus_parameter, bonus_config);
+    let score_after = evaluate_seating(after, students_map, &wishes_map, bonus_parameter, bonus_config);
+    SeatingDiff {
+        distance: moved.len(),
+        moved,
+        changed_table,
+        pairs_gained: only_in(&pairs_after, &pairs_before),
+        pairs_lost: only_in(&pairs_before, &pairs_after),
+        score_before,
+        score_after,
+        score_difference: score_after - score_before,
+    }
+}
+
+fn seats_by_name<'a>(graph: &SeatGraph, arrangement: &'a SeatingArrangement) -> HashMap<&'a String, usize> {
+    (0..graph.coords.len()).filter_map(|s| graph.occupant(arrangement, s).map(|name| (name, s))).collect()
+}
+
+// Unordered neighbour pairs (names sorted) with the strongest adjacency between them.
+fn neighbour_pairs<'a>(graph: &SeatGraph, arrangement: &'a SeatingArrangement) -> BTreeMap<(&'a String, &'a String), AdjacencyKind> {
+    let mut pairs = BTreeMap::new();
+    for seat in 0..graph.coords.len() {
+        let name = match graph.occupant(arrangement, seat) {
+            Some(name) => name,
+            None => continue,
+        };
+        for &(other, kind) in graph.reads(seat) {
+            if let Some(other_name) = graph.occupant(arrangement, other) {
+                let pair = if name < other_name { (name, other_name) } else { (other_name, name) };
+                let entry = pairs.entry(pair).or_insert(kind);
+                if kind.weight() > entry.weight() {
+                    *entry = kind;
+                }
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{arrangement, students};
+    use serde_json::json;
+
+    fn pairs(pairs: &[NeighbourPair]) -> Vec<(&str, &str, AdjacencyKind)> {
+        pairs.iter().map(|p| (p.students[0].as_str(), p.students[1].as_str(), p.kind)).collect()
+    }
+
+    #[test]
+    fn diff_lists_moves_pairs_and_score() {
+        let before = arrangement(json!({"tables": [
+            {"top": ["A", "B", ""], "bottom": ["C", "", ""], "bonus_left": null, "bonus_right": null},
+            {"top": ["D", "", ""], "bottom": ["", "", ""], "bonus_left": null, "bonus_right": null},
+        ]}));
+        let after = arrangement(json!({"tables": [
+            {"top": ["A", "", ""], "bottom": ["C", "D", "B"], "bonus_left": null, "bonus_right": null},
+            {"top": ["", "", ""], "bottom": ["", "", ""], "bonus_left": null, "bonus_right": null},
+        ]}));
+        let students_map = students(&[("A", &["B"]), ("B", &[]), ("C", &["D"]), ("D", &[])]);
+        let diff = diff_seating(&before, &after, &students_map, 1.0, "none");
+
+        let moved: Vec<&str> = diff.moved.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(moved, ["B", "D"]);
+        assert_eq!(diff.distance, 2);
+        assert_eq!(diff.changed_table, ["D"]);
+        assert_eq!(
+            pairs(&diff.pairs_gained),
+            [("A", "D", AdjacencyKind::Diagonal), ("B", "D", AdjacencyKind::Side), ("C", "D", AdjacencyKind::Side)]
+        );
+        assert_eq!(pairs(&diff.pairs_lost), [("A", "B", AdjacencyKind::Side), ("B", "C", AdjacencyKind::Diagonal)]);
+        assert!(diff.pairs_gained.iter().find(|p| p.students == ["C", "D"]).unwrap().wished);
+        assert!((diff.score_difference - (diff.score_after - diff.score_before)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn identical_arrangements_have_an_empty_diff() {
+        let room = arrangement(json!({"tables": [{"top": ["A", ""], "bottom": ["", "B"], "bonus_left": "", "bonus_right": ""}]}));
+        let students_map = students(&[("A", &["B"]), ("B", &["A"])]);
+        let diff = diff_seating(&room, &room, &students_map, 1.0, "both");
+        assert!(diff.moved.is_empty() && diff.pairs_gained.is_empty() && diff.pairs_lost.is_empty());
+        assert_eq!(diff.score_difference, 0.0);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::scoring::Scorer;
+use crate::SeatingArrangement;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// --- Minimal-Disruption Re-optimisation ---
+//
+// Re-optimises the arrangement passed to optimizeSeating while keeping students where they are:
+// every student away from their current ("home") seat costs `moveCost` score, and at most
+// `maxMoves` students may end up away from home. The cost goes into the delta scorer, so every
+// algorithm pays it while it searches. Every algorithm also keeps to the cap while it searches:
+// the annealer and tabu search skip swaps past it, LNS rejects rebuilds past it and the genetic
+// algorithm sends students home in every new individual. The local search phase enforces it once
+// more at the end.
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DisruptionOptions {
+    pub move_cost: f64,
+    pub max_moves: Option<usize>,
+    /// The arrangement being re-optimised; set from the initial arrangement when the mode is on.
+    #[serde(skip)]
+    pub home: Option<SeatingArrangement>,
+}
+
+impl DisruptionOptions {
+    pub fn enabled(&self) -> bool {
+        self.move_cost > 0.0 || self.max_moves.is_some()
+    }
+}
+
+/// Number of students away from their home seat.
+pub fn count_moved(arrangement: &SeatingArrangement, scorer: &Scorer) -> usize {
+    (0..scorer.graph.coords.len()).filter(|&s| scorer.moved(arrangement, s)).count()
+}
+
+/// How the number of students away from home changes if the occupants of `a` and `b` swap.
+/// The arrangement is left as it was.
+pub fn swap_moved_delta(arrangement: &mut SeatingArrangement, scorer: &Scorer, a: usize, b: usize) -> isize {
+    let away = |arrangement: &SeatingArrangement| scorer.moved(arrangement, a) as isize + scorer.moved(arrangement, b) as isize;
+    let before = away(arrangement);
+    scorer.apply_cycle(arrangement, &[a, b]);
+    let after = away(arrangement);
+    scorer.undo_cycle(arrangement, &[a, b]);
+    after - before
+}
+
+/// Sends students home until at most `max_moves` are away, each time picking the move back that
+/// loses the least score. Moving a student home swaps them with whoever sits in their home seat;
+/// only swaps that lower the number of students away are considered, so the loop ends even when
+/// the cap cannot be met (e.g. a home seat outside `free_seats`). Stops early once `cancel` is set.
+/// Returns the score change.
+pub fn enforce_max_moves(
+    arrangement: &mut SeatingArrangement,
+    scorer: &Scorer,
+    free_seats: &[usize],
+    max_moves: usize,
+    cancel: Option<&AtomicBool>,
+) -> f64 {
+    let home_seats = scorer.home_seats();
+    let mut moved = count_moved(arrangement, scorer);
+    let mut total_delta = 0.0;
+    while moved > max_moves && !cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+        let mut best: Option<(f64, usize, usize)> = None;
+        for &seat in free_seats {
+            if !scorer.moved(arrangement, seat) {
+                continue;
+            }
+            let home = match scorer.graph.occupant(arrangement, seat).and_then(|n| home_seats.get(n.as_str())) {
+                Some(&home) if home != seat && free_seats.contains(&home) => home,
+                _ => continue,
+            };
+            if swap_moved_delta(arrangement, scorer, seat, home) >= 0 {
+                continue;
+            }
+            let delta = scorer.swap_delta(arrangement, seat, home);
+            if best.is_none_or(|(d, _, _)| delta > d) {
+                best = Some((delta, seat, home));
+            }
+        }
+        let (delta, seat, home) = match best {
+            Some(m) => m,
+            None => break,
+        };
+        scorer.apply_cycle(arrangement, &[seat, home]);
+        total_delta += delta;
+        moved = count_moved(arrangement, scorer);
+    }
+    total_delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{arrangement, random_case, students};
+    use crate::Coordinate;
+    use rand::prelude::*;
+    use serde_json::json;
+
+    fn options(home: &SeatingArrangement) -> DisruptionOptions {
+        DisruptionOptions { move_cost: 1.0, max_moves: Some(0), home: Some(home.clone()) }
+    }
+
+    #[test]
+    fn enforce_max_moves_sends_students_home() {
+        let home = arrangement(json!({"tables": [{"top": ["A", "", ""], "bottom": ["", "", ""], "bonus_left": null, "bonus_right": null}]}));
+        let mut current = arrangement(json!({"tables": [{"top": ["", "A", ""], "bottom": ["", "", ""], "bonus_left": null, "bonus_right": null}]}));
+        let students_map = students(&[("A", &[])]);
+        let scorer = Scorer::new(&home, &students_map, 1.0, "none").with_disruption(&options(&home));
+        let free_seats: Vec<usize> = (0..scorer.graph.coords.len()).collect();
+        assert_eq!(count_moved(&current, &scorer), 1);
+        enforce_max_moves(&mut current, &scorer, &free_seats, 0, None);
+        assert_eq!(current, home);
+    }
+
+    #[test]
+    fn enforce_max_moves_respects_the_cap() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for bonus_config in ["none", "left", "right", "both"] {
+            let (home, students_map) = random_case(&mut rng, 3, 3, bonus_config, 0.8);
+            let scorer = Scorer::new(&home, &students_map, 1.0, bonus_config).with_disruption(&options(&home));
+            let free_seats: Vec<usize> = (0..scorer.graph.coords.len()).collect();
+            for max_moves in [0, 2, 5] {
+                let mut current = home.clone();
+                for _ in 0..20 {
+                    let (a, b) = (*free_seats.choose(&mut rng).unwrap(), *free_seats.choose(&mut rng).unwrap());
+                    scorer.apply_cycle(&mut current, &[a, b]);
+                }
+                let before = scorer.total_score(&current);
+                let delta = enforce_max_moves(&mut current, &scorer, &free_seats, max_moves, None);
+                assert!(count_moved(&current, &scorer) <= max_moves);
+                assert!((scorer.total_score(&current) - before - delta).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn swap_moved_delta_matches_the_recount() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let (home, students_map) = random_case(&mut rng, 2, 4, "both", 0.7);
+        let scorer = Scorer::new(&home, &students_map, 1.0, "both").with_disruption(&options(&home));
+        let seats: Vec<usize> = (0..scorer.graph.coords.len()).collect();
+        let mut current = home.clone();
+        for _ in 0..200 {
+            let (a, b) = (*seats.choose(&mut rng).unwrap(), *seats.choose(&mut rng).unwrap());
+            let before = count_moved(&current, &scorer) as isize;
+            let delta = swap_moved_delta(&mut current, &scorer, a, b);
+            assert_eq!(count_moved(&current, &scorer) as isize, before);
+            scorer.apply_cycle(&mut current, &[a, b]);
+            assert_eq!(count_moved(&current, &scorer) as isize, before + delta);
+        }
+    }
+
+    #[test]
+    fn enforce_max_moves_stops_when_the_cap_cannot_be_met() {
+        // B's home seat is pinned, so B cannot go back.
+        let home = arrangement(json!({"tables": [{"top": ["A", "B"], "bottom": ["", ""], "bonus_left": null, "bonus_right": null}]}));
+        let mut current = arrangement(json!({"tables": [{"top": ["A", ""], "bottom": ["B", ""], "bonus_left": null, "bonus_right": null}]}));
+        let students_map = students(&[("A", &[]), ("B", &[])]);
+        let scorer = Scorer::new(&home, &students_map, 1.0, "none").with_disruption(&options(&home));
+        let pinned = scorer.graph.id_of(&Coordinate { table: 0, section: "top".to_string(), index: Some(1) }).unwrap();
+        let free_seats: Vec<usize> = (0..scorer.graph.coords.len()).filter(|&s| s != pinned).collect();
+        enforce_max_moves(&mut current, &scorer, &free_seats, 0, None);
+        assert_eq!(count_moved(&current, &scorer), 1);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::scoring::{AdjacencyKind, SeatGraph, GAP_PENALTY};
+use crate::{build_wishes_map, compute_gap_penalty, Coordinate, SeatingArrangement, Student};
+use serde::Serialize;
+use std::collections::HashMap;
+
+// --- Score Explanation ---
+//
+// Breaks `evaluate_seating` down per student and per table, using the same neighbour model, so
+// the UI can show where the score comes from. Student contributions plus the row gap penalties
+// add up to `totalScore`. Gap penalties are the only penalties in the score; they belong to a
+// row rather than a student and are reported per table.
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Neighbour {
+    pub name: String,
+    pub kind: AdjacencyKind,
+    pub wished: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentExplanation {
+    pub name: String,
+    pub seat: Coordinate,
+    pub weight: f64,
+    /// The students this student is scored against.
+    pub neighbours: Vec<Neighbour>,
+    pub met_wishes: Vec<Neighbour>,
+    pub unmet_wishes: Vec<String>,
+    /// Met wishes counted with their adjacency weight (diagonals 0.8).
+    pub fulfilled: f64,
+    /// Whether `bonusParameter` multiplied the contribution (at least one wish met).
+    pub bonus_applied: bool,
+    pub contribution: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableExplanation {
+    pub table: usize,
+    pub students_score: f64,
+    pub top_gaps: usize,
+    pub bottom_gaps: usize,
+    pub gap_penalty: f64,
+    pub total: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatingExplanation {
+    pub total_score: f64,
+    pub gap_penalty: f64,
+    pub students: Vec<StudentExplanation>,
+    pub tables: Vec<TableExplanation>,
+}
+
+pub fn explain_seating(
+    arrangement: &SeatingArrangement,
+    students_map: &HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+) -> SeatingExplanation {
+    let graph = SeatGraph::new(arrangement, bonus_config);
+    let wishes_map = build_wishes_map(students_map);
+    let mut students = Vec::new();
+    let mut tables: Vec<TableExplanation> = arrangement
+        .tables
+        .iter()
+        .enumerate()
+        .map(|(t, table)| {
+            // With a penalty of 1 per gap, the (negated) penalty is the number of gaps.
+            let top_gaps = -compute_gap_penalty(&table.top, 1.0) as usize;
+            let bottom_gaps = -compute_gap_penalty(&table.bottom, 1.0) as usize;
+            let gap_penalty = 0.0 - (top_gaps + bottom_gaps) as f64 * GAP_PENALTY;
+            TableExplanation {
+                table: t,
+                students_score: 0.0,
+                top_gaps,
+                bottom_gaps,
+                gap_penalty,
+                total: gap_penalty,
+            }
+        })
+        .collect();
+
+    for seat in 0..graph.coords.len() {
+        let (name, student) = match graph.occupant(arrangement, seat).and_then(|n| students_map.get(n).map(|s| (n, s))) {
+            Some(found) => found,
+            None => continue,
+        };
+        let wishes = wishes_map.get(name.as_str());
+        let wished = |other: &String| wishes.is_some_and(|w| w.contains(other.as_str()));
+        let neighbours: Vec<Neighbour> = graph
+            .reads(seat)
+            .iter()
+            .filter_map(|&(other, kind)| {
+                graph.occupant(arrangement, other).map(|o| Neighbour { name: o.clone(), kind, wished: wished(o) })
+            })
+            .collect();
+        let met_wishes: Vec<Neighbour> = neighbours
+            .iter()
+            .filter(|n| n.wished)
+            .map(|n| Neighbour { name: n.name.clone(), kind: n.kind, wished: true })
+            .collect();
+        let mut unmet_wishes: Vec<String> = wishes
+            .map(|w| w.iter().filter(|&&x| !met_wishes.iter().any(|m| m.name == x)).map(|x| x.to_string()).collect())
+            .unwrap_or_default();
+        unmet_wishes.sort();
+        let fulfilled = met_wishes.iter().fold(0.0, |sum, m| sum + m.kind.weight());
+        let base_score = fulfilled * student.weight;
+        let contribution = if fulfilled > 0.0 { base_score * bonus_parameter } else { base_score };
+
+        let table = &mut tables[graph.coords[seat].table];
+        table.students_score += contribution;
+        table.total += contribution;
+        students.push(StudentExplanation {
+            name: name.clone(),
+            seat: graph.coords[seat].clone(),
+            weight: student.weight,
+            neighbours,
+            met_wishes,
+            unmet_wishes,
+            fulfilled,
+            bonus_applied: fulfilled > 0.0,
+            contribution,
+        });
+    }
+
+    SeatingExplanation {
+        total_score: tables.iter().fold(0.0, |sum, t| sum + t.total),
+        gap_penalty: tables.iter().fold(0.0, |sum, t| sum + t.gap_penalty),
+        students,
+        tables,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate_seating;
+    use crate::test_util::{arrangement, random_case, students};
+    use rand::prelude::*;
+    use serde_json::json;
+
+    #[test]
+    fn explanation_lists_neighbours_unmet_wishes_and_gaps() {
+        let room = arrangement(json!({"tables": [{"top": ["A", "", "B"], "bottom": ["", "C", ""], "bonus_left": "", "bonus_right": null}]}));
+        let students_map = students(&[("A", &["B", "C"]), ("B", &[]), ("C", &[])]);
+        let explanation = explain_seating(&room, &students_map, 1.0, "left");
+        let a = explanation.students.iter().find(|s| s.name == "A").unwrap();
+        let neighbours: Vec<&str> = a.neighbours.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(neighbours, ["C"]);
+        assert_eq!(a.unmet_wishes, ["B"]);
+        assert_eq!(explanation.tables[0].top_gaps, 1);
+        assert_eq!(explanation.tables[0].bottom_gaps, 0);
+        assert_eq!(explanation.students.len(), 3);
+    }
+
+    #[test]
+    fn explanation_adds_up_to_evaluate_seating() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for bonus_config in ["none", "left", "right", "both"] {
+            let (room, students_map) = random_case(&mut rng, 3, 4, bonus_config, 0.7);
+            let explanation = explain_seating(&room, &students_map, 1.5, bonus_config);
+            let expected = evaluate_seating(&room, &students_map, &build_wishes_map(&students_map), 1.5, bonus_config);
+            assert!((explanation.total_score - expected).abs() < 1e-9);
+        }
+    }
+}
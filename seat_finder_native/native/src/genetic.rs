@@ -0,0 +1,347 @@
+use crate::acceptance::AcceptanceStats;
+use crate::disruption;
+use crate::lns::repair;
+use crate::local_search::{local_search, LocalSearchOptions, LocalSearchStrategy};
+use crate::objective::Ranked;
+use crate::scoring::Scorer;
+use crate::trace::Trace;
+use crate::{
+    build_free_coords, build_wishes_map, is_best_possible, is_cancelled, local_search_phase, max_satisfied,
+    report_population, report_progress, report_snapshot, Coordinate, OptimizeOptions, PerformanceLog, PopulationStats,
+    SeatingArrangement, Student,
+};
+use rand::prelude::*;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::thread;
+use std::time::Instant;
+
+// --- Memetic Algorithm ---
+//
+// A population of arrangements evolves by table-preserving crossover, swap mutation and a short
+// local search on every child. Children are bred in parallel; the next generation is the best
+// `populationSize` distinct arrangements among parents and children. A run lasts `generations`
+// generations, and progress counts generations rather than annealing iterations. Individuals are
+// ranked as `Ranked` pairs, so under the lexicographic objective selection and replacement favour
+// more satisfied students before a higher score.
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GeneticOptions {
+    pub population_size: usize,
+    pub generations: usize,
+    pub mutation_rate: f64,
+    pub mutation_swaps: usize,
+    pub polish_ms: u64,
+    /// Worker threads breeding children; set from the number of parallel runs.
+    #[serde(skip)]
+    pub threads: usize,
+}
+
+impl Default for GeneticOptions {
+    fn default() -> Self {
+        GeneticOptions {
+            population_size: 24,
+            generations: 100,
+            mutation_rate: 0.3,
+            mutation_swaps: 3,
+            polish_ms: 20,
+            threads: 1,
+        }
+    }
+}
+
+// Probability of taking a table from the parent where it scores higher.
+const BETTER_TABLE_BIAS: f64 = 0.7;
+
+struct Individual {
+    arrangement: SeatingArrangement,
+    ranked: Ranked,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn optimize_seating_genetic(
+    initial_arrangement: SeatingArrangement,
+    fixed_coords: Vec<Coordinate>,
+    students_map: HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+    early_stop: bool,
+    options: &OptimizeOptions,
+    run_id: usize,
+) -> (SeatingArrangement, PerformanceLog) {
+    let start = Instant::now();
+    let genetic = &options.genetic;
+    let population_size = genetic.population_size.max(2);
+    let generations = genetic.generations.max(1);
+    let threads = genetic.threads.max(1);
+    let wishes_map = build_wishes_map(&students_map);
+    let scorer = Scorer::new(&initial_arrangement, &students_map, bonus_parameter, bonus_config)
+        .with_disruption(&options.disruption)
+        .with_objective(options.objective);
+    let max_satisfied = max_satisfied(&scorer, &initial_arrangement, options.objective);
+    let mut log_messages = Vec::new();
+
+    let free_coords = build_free_coords(&initial_arrangement, &fixed_coords, bonus_config);
+    let free_seats: Vec<usize> = free_coords.iter().filter_map(|c| scorer.graph.id_of(c)).collect();
+    let mut table_seats: Vec<Vec<usize>> = vec![Vec::new(); initial_arrangement.tables.len()];
+    for &seat in &free_seats {
+        table_seats[scorer.graph.coords[seat].table].push(seat);
+    }
+    let free_students: Vec<String> =
+        free_seats.iter().filter_map(|&s| scorer.graph.occupant(&initial_arrangement, s).cloned()).collect();
+    let polish_options = LocalSearchOptions {
+        strategy: LocalSearchStrategy::First,
+        time_limit_ms: genetic.polish_ms,
+        three_swap: false,
+        cancel: None,
+    };
+    let evaluate = |mut arrangement: SeatingArrangement| {
+        // Every individual keeps to the minimal-disruption move cap.
+        if let Some(max) = options.disruption.max_moves {
+            disruption::enforce_max_moves(&mut arrangement, &scorer, &free_seats, max, None);
+        }
+        let ranked = scorer.total_ranked(&arrangement);
+        Individual { arrangement, ranked }
+    };
+
+    // The initial arrangement plus random reshuffles of its free seats, all polished.
+    let mut rng = thread_rng();
+    let mut population: Vec<Individual> = (0..population_size)
+        .map(|i| {
+            let mut arrangement = initial_arrangement.clone();
+            if i > 0 {
+                let mut occupants: Vec<Option<String>> =
+                    free_seats.iter().map(|&s| scorer.graph.seat_mut(&mut arrangement, s).take()).collect();
+                occupants.shuffle(&mut rng);
+                for (&seat, occupant) in free_seats.iter().zip(occupants) {
+                    *scorer.graph.seat_mut(&mut arrangement, seat) = occupant;
+                }
+            }
+            local_search(&mut arrangement, &free_seats, &scorer, &polish_options);
+            evaluate(arrangement)
+        })
+        .collect();
+    population.sort_by(|a, b| b.ranked.total_cmp(&a.ranked));
+
+    let mut trace = Trace::new(&options.trace);
+    let mut generations_done = 0;
+    let mut early_stop_iteration = None;
+    let mut children_bred = 0;
+    for generation in 0..generations {
+        generations_done = generation + 1;
+        if trace.due(generation) {
+            // The population mean stands in for the current score; there is no accept step.
+            let mean = population.iter().fold(0.0, |sum, i| sum + i.ranked.score) / population.len() as f64;
+            trace.record(generation, population[0].ranked.score, mean, 0.0, &AcceptanceStats::default());
+        }
+        let best = &population[0];
+        report_progress(options, generation, best.ranked.score, 0.0);
+        report_snapshot(options, &best.arrangement, best.ranked.score);
+        report_population(options, population_stats(generation, &population, &free_seats, &scorer));
+        if early_stop && is_best_possible(&best.arrangement, best.ranked.satisfied, max_satisfied, &students_map, &wishes_map) {
+            log_messages.push(format!("Run {}: Early stopping at generation {}", run_id, generation));
+            early_stop_iteration = Some(generation);
+            break;
+        }
+        if is_cancelled(options) {
+            log_messages.push(format!("Run {}: Cancelled at generation {}", run_id, generation));
+            break;
+        }
+
+        // Breed `population_size` children, split evenly over the worker threads.
+        let per_thread = population_size.div_ceil(threads);
+        let children: Vec<Individual> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|w| {
+                    let count = per_thread.min(population_size.saturating_sub(w * per_thread));
+                    let (population, scorer, table_seats, free_seats, free_students) =
+                        (&population, &scorer, &table_seats, &free_seats, &free_students);
+                    let (polish_options, evaluate) = (&polish_options, &evaluate);
+                    scope.spawn(move || {
+                        let mut rng = thread_rng();
+                        (0..count)
+                            .map(|_| {
+                                let a = tournament(population, &mut rng);
+                                let b = tournament(population, &mut rng);
+                                let mut child = crossover(
+                                    &a.arrangement,
+                                    &b.arrangement,
+                                    scorer,
+                                    table_seats,
+                                    free_seats,
+                                    free_students,
+                                    &mut rng,
+                                );
+                                if rng.gen_bool(genetic.mutation_rate.clamp(0.0, 1.0)) {
+                                    mutate(&mut child, scorer, free_seats, genetic.mutation_swaps, &mut rng);
+                                }
+                                local_search(&mut child, free_seats, scorer, polish_options);
+                                evaluate(child)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+        });
+
+        // Elitist replacement: keep the best distinct arrangements of parents and children.
+        children_bred += children.len();
+        population.extend(children);
+        population.sort_by(|a, b| b.ranked.total_cmp(&a.ranked));
+        let mut next: Vec<Individual> = Vec::with_capacity(population_size);
+        for individual in population {
+            if next.len() == population_size {
+                break;
+            }
+            if !next.iter().any(|kept| kept.arrangement == individual.arrangement) {
+                next.push(individual);
+            }
+        }
+        population = next;
+    }
+
+    let mean = population.iter().fold(0.0, |sum, i| sum + i.ranked.score) / population.len() as f64;
+    let trace = trace.finish(
+        generations_done,
+        population[0].ranked.score,
+        mean,
+        0.0,
+        &AcceptanceStats::default(),
+    );
+    let mut best_arrangement = population.swap_remove(0).arrangement;
+    // Local search phase.
+    let (_, local_search_stats) = local_search_phase(
+        &mut best_arrangement,
+        &free_coords,
+        &students_map,
+        bonus_parameter,
+        bonus_config,
+        options,
+        run_id,
+        &mut log_messages,
+    );
+    let optimization_time = start.elapsed();
+    log_messages.push(format!(
+        "Run {}: Genetic search ran {} generations of {} on {} threads",
+        run_id, generations_done, population_size, threads
+    ));
+    log_messages.push(format!("Run {}: Total optimization time: {:?}", run_id, optimization_time));
+    let log_summary = log_messages.join("\n");
+    let perf_log = PerformanceLog {
+        run_id,
+        total_iterations: generations_done,
+        optimization_time,
+        local_search: local_search_stats,
+        acceptance: AcceptanceStats::default(),
+        trace,
+        early_stop_iteration,
+        // Every child bred counts as one move.
+        moves_evaluated: children_bred,
+        log_summary,
+    };
+    (best_arrangement, perf_log)
+}
+
+// Binary tournament selection.
+fn tournament<'p, R: Rng>(population: &'p [Individual], rng: &mut R) -> &'p Individual {
+    let a = &population[rng.gen_range(0..population.len())];
+    let b = &population[rng.gen_range(0..population.len())];
+    if b.ranked.beats(a.ranked, 0.0) { b } else { a }
+}
+
+/// Builds a child table by table: each table's free seats are copied from one parent, preferring
+/// the parent where that table scores higher. Students already placed from an earlier table are
+/// skipped, and whoever is left unplaced at the end is inserted greedily into the empty seats.
+fn crossover<R: Rng>(
+    parent_a: &SeatingArrangement,
+    parent_b: &SeatingArrangement,
+    scorer: &Scorer,
+    table_seats: &[Vec<usize>],
+    free_seats: &[usize],
+    free_students: &[String],
+    rng: &mut R,
+) -> SeatingArrangement {
+    let mut child = parent_a.clone();
+    for &seat in free_seats {
+        *scorer.graph.seat_mut(&mut child, seat) = None;
+    }
+    let mut order: Vec<usize> = (0..table_seats.len()).filter(|&t| !table_seats[t].is_empty()).collect();
+    order.shuffle(rng);
+    let mut placed: HashSet<&str> = HashSet::with_capacity(free_students.len());
+    for t in order {
+        let a_better = scorer.table_score(parent_a, t) >= scorer.table_score(parent_b, t);
+        let take_a = a_better == rng.gen_bool(BETTER_TABLE_BIAS);
+        let source = if take_a { parent_a } else { parent_b };
+        for &seat in &table_seats[t] {
+            if let Some(name) = scorer.graph.occupant(source, seat) {
+                if placed.insert(name.as_str()) {
+                    *scorer.graph.seat_mut(&mut child, seat) = Some(name.clone());
+                }
+            }
+        }
+    }
+    let missing: Vec<String> = free_students.iter().filter(|s| !placed.contains(s.as_str())).cloned().collect();
+    repair(&mut child, scorer, free_seats, missing, rng);
+    child
+}
+
+fn mutate<R: Rng>(arrangement: &mut SeatingArrangement, scorer: &Scorer, free_seats: &[usize], swaps: usize, rng: &mut R) {
+    if free_seats.len() < 2 {
+        return;
+    }
+    for _ in 0..swaps.max(1) {
+        let a = free_seats[rng.gen_range(0..free_seats.len())];
+        let b = free_seats[rng.gen_range(0..free_seats.len())];
+        if a != b {
+            scorer.apply_cycle(arrangement, &[a, b]);
+        }
+    }
+}
+
+fn population_stats(generation: usize, population: &[Individual], free_seats: &[usize], scorer: &Scorer) -> PopulationStats {
+    let best = &population[0];
+    let mean = population.iter().map(|i| i.ranked.score).sum::<f64>() / population.len() as f64;
+    // Average number of free seats whose occupant differs from the best arrangement.
+    let distance = population[1..]
+        .iter()
+        .map(|i| {
+            free_seats
+                .iter()
+                .filter(|&&s| scorer.graph.occupant(&i.arrangement, s) != scorer.graph.occupant(&best.arrangement, s))
+                .count() as f64
+        })
+        .sum::<f64>()
+        / (population.len() - 1).max(1) as f64;
+    PopulationStats {
+        generation,
+        size: population.len(),
+        best_score: best.ranked.score,
+        mean_score: mean,
+        worst_score: population[population.len() - 1].ranked.score,
+        mean_distance_to_best: distance,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objective::Objective;
+    use crate::test_util::split_pair;
+
+    #[test]
+    fn population_ranks_satisfied_students_before_score() {
+        let (room, students_map) = split_pair();
+        let options = OptimizeOptions {
+            objective: Objective::Lexicographic,
+            genetic: GeneticOptions { population_size: 8, generations: 3, polish_ms: 1, ..GeneticOptions::default() },
+            ..OptimizeOptions::default()
+        };
+        let (best, log) = optimize_seating_genetic(room.clone(), Vec::new(), students_map.clone(), 1.0, "none", false, &options, 0);
+        // The fittest individual of the last generation, before the local search phase.
+        assert_eq!(log.trace.last().unwrap().best_score, 2.0);
+        let scorer = Scorer::new(&room, &students_map, 1.0, "none").with_objective(Objective::Lexicographic);
+        assert_eq!(scorer.total_ranked(&best), Ranked { satisfied: 2, score: 2.0 });
+    }
+}
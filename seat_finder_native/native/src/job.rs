@@ -0,0 +1,319 @@
+use crate::{PopulationStats, SeatingArrangement};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+// --- Optimisation Jobs ---
+//
+// Every optimizeSeating call is a job with its own progress and result, so concurrent runs
+// cannot see each other's data. Jobs live in a process-wide registry keyed by id until their
+// result has been collected, or for UNCOLLECTED_TTL after they finish if nobody collects it. A job can be cancelled; the optimisers check the flag
+// cooperatively and finish with the best arrangement found so far. A job may also carry a
+// progress sink, which the worker threads call with the progress at most once per interval.
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProgressOptions {
+    /// When set, progress also carries the best arrangement so far, refreshed at most this often.
+    pub snapshot_interval_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressInfo {
+    iteration: usize,
+    best_score: f64,
+    temperature: f64,
+    // Only set by the genetic algorithm.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    population: Option<PopulationStats>,
+    // Only set when snapshots are on; the best arrangement over all runs so far.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_arrangement: Option<SeatingArrangement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_arrangement_score: Option<f64>,
+    finished: bool,
+    cancelled: bool,
+}
+
+/// What a progress sink receives: the progress so far, with the best arrangement over all runs
+/// when snapshots are on.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressEvent {
+    job_id: u64,
+    iteration: usize,
+    best_score: f64,
+    temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    population: Option<PopulationStats>,
+    seating_arrangement: Option<SeatingArrangement>,
+}
+
+pub struct ProgressSink {
+    interval_ms: u64,
+    notify: Box<dyn Fn(ProgressEvent) + Send + Sync>,
+}
+
+impl ProgressSink {
+    /// Calls `notify` from the worker threads, at most once every `interval_ms`.
+    pub fn new(interval_ms: u64, notify: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        ProgressSink { interval_ms, notify: Box::new(notify) }
+    }
+}
+
+impl fmt::Debug for ProgressSink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProgressSink").field("interval_ms", &self.interval_ms).finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+pub struct Job {
+    pub id: u64,
+    started: Instant,
+    // Milliseconds after `started` at which the last snapshot was taken.
+    last_snapshot_ms: AtomicU64,
+    // Milliseconds after `started` at which the progress sink was last called.
+    last_notified_ms: AtomicU64,
+    progress_sink: OnceCell<ProgressSink>,
+    finished_at: OnceCell<Instant>,
+    cancelled: Arc<AtomicBool>,
+    progress: Mutex<ProgressInfo>,
+    result: Mutex<Option<Result<String, String>>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSummary {
+    id: u64,
+    finished: bool,
+    cancelled: bool,
+    iteration: usize,
+    best_score: f64,
+    elapsed_ms: f64,
+}
+
+static JOBS: Lazy<Mutex<HashMap<u64, Arc<Job>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+// How long a finished job keeps its result for getResult before the registry drops it.
+const UNCOLLECTED_TTL: Duration = Duration::from_secs(15 * 60);
+
+impl Job {
+    fn new(initial_temperature: f64) -> Self {
+        Job {
+            id: NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed),
+            started: Instant::now(),
+            last_snapshot_ms: AtomicU64::new(0),
+            last_notified_ms: AtomicU64::new(0),
+            progress_sink: OnceCell::new(),
+            finished_at: OnceCell::new(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            progress: Mutex::new(ProgressInfo {
+                iteration: 0,
+                best_score: f64::MIN,
+                temperature: initial_temperature,
+                population: None,
+                best_arrangement: None,
+                best_arrangement_score: None,
+                finished: false,
+                cancelled: false,
+            }),
+            result: Mutex::new(None),
+        }
+    }
+
+    pub fn report_progress(&self, iteration: usize, best_score: f64, temperature: f64) {
+        {
+            let mut prog = lock(&self.progress);
+            prog.iteration = iteration;
+            prog.best_score = best_score;
+            prog.temperature = temperature;
+        }
+        if let Some(sink) = self.progress_sink.get() {
+            if claim_slot(&self.started, &self.last_notified_ms, sink.interval_ms) {
+                (sink.notify)(self.progress_event());
+            }
+        }
+    }
+
+    /// Sets the sink `report_progress` notifies. A job has at most one; returns false if it
+    /// already had one.
+    pub fn set_progress_sink(&self, sink: ProgressSink) -> bool {
+        self.progress_sink.set(sink).is_ok()
+    }
+
+    fn progress_event(&self) -> ProgressEvent {
+        let prog = self.progress();
+        ProgressEvent {
+            job_id: self.id,
+            iteration: prog.iteration,
+            best_score: prog.best_score,
+            temperature: prog.temperature,
+            population: prog.population,
+            seating_arrangement: prog.best_arrangement,
+        }
+    }
+
+    pub fn report_population(&self, stats: PopulationStats) {
+        lock(&self.progress).population = Some(stats);
+    }
+
+    /// Whether a new snapshot may be taken, `interval_ms` after the previous one. Claims the
+    /// slot, so of several runs asking at once only one gets `true`.
+    pub fn snapshot_due(&self, interval_ms: u64) -> bool {
+        claim_slot(&self.started, &self.last_snapshot_ms, interval_ms)
+    }
+
+    /// Whether `score` beats the arrangement in the current snapshot.
+    pub fn improves_snapshot(&self, score: f64) -> bool {
+        lock(&self.progress).best_arrangement_score.is_none_or(|s| score > s)
+    }
+
+    pub fn report_snapshot(&self, arrangement: SeatingArrangement, score: f64) {
+        let mut prog = lock(&self.progress);
+        if prog.best_arrangement_score.is_none_or(|s| score > s) {
+            prog.best_arrangement = Some(arrangement);
+            prog.best_arrangement_score = Some(score);
+        }
+    }
+
+    /// Stores the final result (a JSON string, or why the run failed) and marks the job finished.
+    pub fn finish(&self, outcome: Result<String, String>) {
+        *lock(&self.result) = Some(outcome);
+        lock(&self.progress).finished = true;
+        let _ = self.finished_at.set(Instant::now());
+    }
+
+    // Whether the job finished more than UNCOLLECTED_TTL before `now`.
+    fn expired(&self, now: Instant) -> bool {
+        self.finished_at.get().is_some_and(|at| now.saturating_duration_since(*at) >= UNCOLLECTED_TTL)
+    }
+
+    /// Asks the optimisers to stop. Returns false if the job had already finished.
+    pub fn cancel(&self) -> bool {
+        let mut prog = lock(&self.progress);
+        if prog.finished {
+            return false;
+        }
+        prog.cancelled = true;
+        self.cancelled.store(true, Ordering::Relaxed);
+        true
+    }
+
+    #[inline(always)]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// The cancel flag on its own, for code that does not know about jobs.
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    pub fn progress(&self) -> ProgressInfo {
+        lock(&self.progress).clone()
+    }
+
+    fn summary(&self) -> JobSummary {
+        let prog = self.progress();
+        JobSummary {
+            id: self.id,
+            finished: prog.finished,
+            cancelled: prog.cancelled,
+            iteration: prog.iteration,
+            best_score: prog.best_score,
+            elapsed_ms: self.started.elapsed().as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Registers a new job, starting at `initial_temperature`. Finished jobs whose result has waited
+/// longer than UNCOLLECTED_TTL are dropped first.
+pub fn start(initial_temperature: f64) -> Arc<Job> {
+    let job = Arc::new(Job::new(initial_temperature));
+    let mut jobs = lock(&JOBS);
+    evict_expired(&mut jobs, Instant::now());
+    jobs.insert(job.id, job.clone());
+    job
+}
+
+fn evict_expired(jobs: &mut HashMap<u64, Arc<Job>>, now: Instant) {
+    jobs.retain(|_, job| !job.expired(now));
+}
+
+// Locks `mutex` even if a thread panicked while holding it. Job state is plain data that stays
+// consistent between statements, so a failed run must not make its job unreadable.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Moves `last` (milliseconds after `started`) to now if `interval_ms` have passed since, and
+// says whether this caller did so.
+fn claim_slot(started: &Instant, last: &AtomicU64, interval_ms: u64) -> bool {
+    let now = started.elapsed().as_millis() as u64;
+    let previous = last.load(Ordering::Relaxed);
+    now >= previous + interval_ms && last.compare_exchange(previous, now, Ordering::Relaxed, Ordering::Relaxed).is_ok()
+}
+
+pub fn get(id: u64) -> Option<Arc<Job>> {
+    lock(&JOBS).get(&id).cloned()
+}
+
+/// The final result of a finished job. Collecting it removes the job from the registry.
+pub fn take_result(id: u64) -> Option<Result<String, String>> {
+    let mut jobs = lock(&JOBS);
+    let result = lock(&jobs.get(&id)?.result).take()?;
+    jobs.remove(&id);
+    Some(result)
+}
+
+pub fn list() -> Vec<JobSummary> {
+    let mut summaries: Vec<JobSummary> = lock(&JOBS).values().map(|job| job.summary()).collect();
+    summaries.sort_by_key(|s| s.id);
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn only_finished_jobs_past_the_ttl_are_evicted() {
+        let running = Arc::new(Job::new(1.0));
+        let fresh = Arc::new(Job::new(1.0));
+        fresh.finish(Ok("{}".to_string()));
+        let mut jobs: HashMap<u64, Arc<Job>> = vec![running.clone(), fresh.clone()].into_iter().map(|job| (job.id, job)).collect();
+
+        evict_expired(&mut jobs, Instant::now());
+        assert_eq!(jobs.len(), 2);
+
+        evict_expired(&mut jobs, Instant::now() + UNCOLLECTED_TTL);
+        assert!(jobs.contains_key(&running.id));
+        assert!(!jobs.contains_key(&fresh.id));
+    }
+
+    #[test]
+    fn a_poisoned_job_can_still_be_cancelled_and_read() {
+        let job = Arc::new(Job::new(1.0));
+        let holder = job.clone();
+        let _ = thread::spawn(move || {
+            let _prog = holder.progress.lock().unwrap();
+            panic!("run failed while reporting progress");
+        })
+        .join();
+        assert!(job.progress.is_poisoned());
+
+        assert!(job.cancel());
+        job.finish(Err("run failed".to_string()));
+        assert!(job.progress().cancelled);
+        assert!(job.progress().finished);
+        assert!(!job.cancel());
+    }
+}
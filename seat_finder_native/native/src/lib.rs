@@ -1,32 +1,108 @@
 use neon::prelude::*;
 use rand::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::json;
+use std::any::Any;
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
-use std::sync::mpsc::channel; // used for aggregating parallel runs (though not exposed via Neon)
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::mpsc::channel; // collects the results of the parallel runs
 use std::thread;
 use std::time::Instant;
-use once_cell::sync::Lazy;
 
-// --- Global Shared Progress State ---
-// This state will be updated during optimization and can be polled via getProgress.
+mod acceptance;
+mod cooling;
+mod diff;
+mod disruption;
+mod explain;
+mod genetic;
+mod job;
+mod lns;
+mod local_search;
+mod moves;
+mod objective;
+mod roster;
+mod scoring;
+mod statistics;
+mod tabu;
+#[cfg(test)]
+mod test_util;
+mod trace;
+mod validate;
+mod wishes;
+
+use acceptance::{AcceptanceOptions, AcceptanceStats, Acceptor};
+use cooling::{Cooler, CoolingOptions};
+use disruption::DisruptionOptions;
+use genetic::GeneticOptions;
+use job::ProgressOptions;
+use lns::LnsOptions;
+use local_search::{local_search, LocalSearchOptions, LocalSearchStats};
+use objective::{Objective, Ranked};
+use tabu::TabuOptions;
+use scoring::{SeatGraph, Scorer};
+use trace::{Trace, TraceOptions, TracePoint};
+
+// --- Progress Reporting ---
+// Optimisers report progress to the job carried in their options, if any; getProgress reads it.
 #[derive(Clone, Debug, Serialize)]
-struct ProgressInfo {
-    iteration: usize,
+#[serde(rename_all = "camelCase")]
+pub struct PopulationStats {
+    generation: usize,
+    size: usize,
     best_score: f64,
-    temperature: f64,
-    final_result: Option<String>,
+    mean_score: f64,
+    worst_score: f64,
+    mean_distance_to_best: f64,
+}
+
+fn report_progress(options: &OptimizeOptions, iteration: usize, best_score: f64, temperature: f64) {
+    if let Some(job) = &options.job {
+        job.report_progress(iteration, best_score, temperature);
+    }
+}
+
+// Offers `arrangement` as the job's best-so-far snapshot; only cloned when it is both due and
+// better than the one already there.
+fn report_snapshot(options: &OptimizeOptions, arrangement: &SeatingArrangement, score: f64) {
+    if let (Some(job), Some(interval)) = (&options.job, options.progress.snapshot_interval_ms) {
+        if job.improves_snapshot(score) && job.snapshot_due(interval) {
+            job.report_snapshot(arrangement.clone(), score);
+        }
+    }
+}
+
+fn report_population(options: &OptimizeOptions, stats: PopulationStats) {
+    if let Some(job) = &options.job {
+        job.report_population(stats);
+    }
+}
+
+// Whether cancelOptimization was called for the job; optimisers check this cooperatively.
+#[inline(always)]
+fn is_cancelled(options: &OptimizeOptions) -> bool {
+    options.job.as_ref().is_some_and(|job| job.is_cancelled())
+}
+
+// The most satisfied students a run can reach under the lexicographic objective: every seated
+// student with wishes, diagonals included, which is the scorer's own count. None otherwise.
+fn max_satisfied(scorer: &Scorer, arrangement: &SeatingArrangement, objective: Objective) -> Option<i64> {
+    (objective == Objective::Lexicographic).then(|| scorer.wishing(arrangement))
 }
 
-static GLOBAL_PROGRESS: Lazy<Arc<Mutex<ProgressInfo>>> = Lazy::new(|| {
-    Arc::new(Mutex::new(ProgressInfo {
-        iteration: 0,
-        best_score: std::f64::MIN,
-        temperature: 0.0,
-        final_result: None,
-    }))
-});
+// The early-stop test: all `max_satisfied` students satisfied if set, a perfect seating otherwise.
+fn is_best_possible(
+    best_arrangement: &SeatingArrangement,
+    best_satisfied: i64,
+    max_satisfied: Option<i64>,
+    students_map: &HashMap<String, Student>,
+    wishes_map: &HashMap<&str, HashSet<&str>>,
+) -> bool {
+    match max_satisfied {
+        Some(max) => best_satisfied == max,
+        None => is_perfect_seating(best_arrangement, students_map, wishes_map),
+    }
+}
 
 // --- Domain Types ---
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -38,37 +114,159 @@ pub struct Student {
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Table {
+    #[serde(deserialize_with = "deserialize_seats")]
     pub top: Vec<Option<String>>,
+    #[serde(deserialize_with = "deserialize_seats")]
     pub bottom: Vec<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_seat")]
     pub bonus_left: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_seat")]
     pub bonus_right: Option<String>,
 }
 
+// Empty seats arrive as null or as "" (SeatingArrangement.createEmpty and the routes use ""). Both
+// become None here, so every module sees the same empty seat.
+fn deserialize_seat<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.filter(|name| !name.is_empty()))
+}
+
+fn deserialize_seats<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Option<String>>, D::Error> {
+    let seats = Vec::<Option<String>>::deserialize(deserializer)?;
+    Ok(seats.into_iter().map(|seat| seat.filter(|name| !name.is_empty())).collect())
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct SeatingArrangement {
     pub tables: Vec<Table>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct Coordinate {
     pub table: usize,
     pub section: String,      // "top", "bottom", "bonus_left", "bonus_right"
     pub index: Option<usize>, // Some(index) for top/bottom; None for bonus seats.
 }
 
+// --- Optimization Options ---
+// Optional settings passed to optimizeSeating as a JSON string; anything omitted keeps its default.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Algorithm {
+    #[default]
+    Annealing,
+    Lns,
+    Tabu,
+    Genetic,
+}
+
+impl Algorithm {
+    /// What one of the algorithm's iterations is, as counted in progress and telemetry.
+    pub fn iteration_unit(self) -> &'static str {
+        match self {
+            Algorithm::Annealing => "iterations",
+            Algorithm::Lns | Algorithm::Tabu => "steps",
+            Algorithm::Genetic => "generations",
+        }
+    }
+}
+
+/// How many of the best run results to return, and how many students must sit elsewhere for
+/// two of them to count as different alternatives.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AlternativesOptions {
+    pub count: usize,
+    pub min_distance: usize,
+}
+
+impl Default for AlternativesOptions {
+    fn default() -> Self {
+        AlternativesOptions { count: 3, min_distance: 4 }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OptimizeOptions {
+    pub algorithm: Algorithm,
+    pub objective: Objective,
+    pub alternatives: AlternativesOptions,
+    pub acceptance: AcceptanceOptions,
+    pub cooling: CoolingOptions,
+    pub disruption: DisruptionOptions,
+    pub local_search: LocalSearchOptions,
+    pub lns: LnsOptions,
+    pub tabu: TabuOptions,
+    pub genetic: GeneticOptions,
+    pub progress: ProgressOptions,
+    pub trace: TraceOptions,
+    /// The job this run reports progress to; set by optimizeSeating.
+    #[serde(skip)]
+    pub job: Option<Arc<job::Job>>,
+}
+
 // --- Performance Logging ---
 struct PerformanceLog {
     run_id: usize,
     total_iterations: usize,
-    best_score: f64,
     optimization_time: std::time::Duration,
-    local_search_time: std::time::Duration,
+    local_search: LocalSearchStats,
+    acceptance: AcceptanceStats,
+    trace: Vec<TracePoint>,
+    /// Iteration at which the run found a perfect seating and stopped, if early stop did that.
+    early_stop_iteration: Option<usize>,
+    /// Candidate moves scored by the search itself (local search is counted separately).
+    moves_evaluated: usize,
     log_summary: String,
 }
 
+// Per-run numbers for the result JSON and the log. Unlike `alternatives`, this covers every run.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTelemetry {
+    run_id: usize,
+    algorithm: Algorithm,
+    score: f64,
+    /// Counted in `iteration_unit`, which depends on the algorithm.
+    iterations: usize,
+    iteration_unit: &'static str,
+    early_stop_iteration: Option<usize>,
+    moves_evaluated: usize,
+    search_time_ms: f64,
+    local_search_time_ms: f64,
+    total_time_ms: f64,
+    moves_per_second: f64,
+    acceptance_ratio: Option<f64>,
+}
+
+impl RunTelemetry {
+    fn new(log: &PerformanceLog, algorithm: Algorithm, score: f64) -> Self {
+        let search_time = log.optimization_time.saturating_sub(log.local_search.time);
+        let decisions = log.acceptance.improving + log.acceptance.accepted_worse + log.acceptance.rejected;
+        RunTelemetry {
+            run_id: log.run_id,
+            algorithm,
+            score,
+            iterations: log.total_iterations,
+            iteration_unit: algorithm.iteration_unit(),
+            early_stop_iteration: log.early_stop_iteration,
+            moves_evaluated: log.moves_evaluated,
+            search_time_ms: search_time.as_secs_f64() * 1000.0,
+            local_search_time_ms: log.local_search.time.as_secs_f64() * 1000.0,
+            total_time_ms: log.optimization_time.as_secs_f64() * 1000.0,
+            moves_per_second: if search_time.as_secs_f64() > 0.0 {
+                log.moves_evaluated as f64 / search_time.as_secs_f64()
+            } else {
+                0.0
+            },
+            acceptance_ratio: (decisions > 0).then(|| log.acceptance.acceptance_ratio()),
+        }
+    }
+}
+
 // --- Helper Functions ---
 #[inline(always)]
-fn compute_gap_penalty(row: &Vec<Option<String>>, gap_penalty: f64) -> f64 {
+fn compute_gap_penalty(row: &[Option<String>], gap_penalty: f64) -> f64 {
     let mut first: Option<usize> = None;
     let mut last: Option<usize> = None;
     let mut count = 0;
@@ -90,9 +288,9 @@ fn compute_gap_penalty(row: &Vec<Option<String>>, gap_penalty: f64) -> f64 {
     }
 }
 
-fn build_wishes_map<'a>(
-    students_map: &'a HashMap<String, Student>
-) -> HashMap<&'a str, HashSet<&'a str>> {
+fn build_wishes_map(
+    students_map: &HashMap<String, Student>
+) -> HashMap<&str, HashSet<&str>> {
     let mut wishes_map = HashMap::with_capacity(students_map.len());
     for (name, student) in students_map.iter() {
         let set: HashSet<&str> = student.wishes.iter().map(|s| s.as_str()).collect();
@@ -121,17 +319,17 @@ pub fn evaluate_seating(
                     let wishes = wishes_map.get(student_name.as_str()).unwrap();
                     let mut fulfilled = 0.0;
                     if i > 0 {
-                        if let Some(ref neighbor) = table.top[i - 1].as_ref() {
+                        if let Some(neighbor) = table.top[i - 1].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1.0; }
                         }
                     }
                     if i + 1 < top_len {
-                        if let Some(ref neighbor) = table.top[i + 1].as_ref() {
+                        if let Some(neighbor) = table.top[i + 1].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1.0; }
                         }
                     }
                     if i < bottom_len {
-                        if let Some(ref neighbor) = table.bottom[i].as_ref() {
+                        if let Some(neighbor) = table.bottom[i].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1.0; }
                         }
                     }
@@ -157,17 +355,17 @@ pub fn evaluate_seating(
                     let wishes = wishes_map.get(student_name.as_str()).unwrap();
                     let mut fulfilled = 0.0;
                     if i > 0 {
-                        if let Some(ref neighbor) = table.bottom[i - 1].as_ref() {
+                        if let Some(neighbor) = table.bottom[i - 1].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1.0; }
                         }
                     }
                     if i + 1 < bottom_len {
-                        if let Some(ref neighbor) = table.bottom[i + 1].as_ref() {
+                        if let Some(neighbor) = table.bottom[i + 1].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1.0; }
                         }
                     }
                     if i < top_len {
-                        if let Some(ref neighbor) = table.top[i].as_ref() {
+                        if let Some(neighbor) = table.top[i].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1.0; }
                         }
                     }
@@ -192,10 +390,10 @@ pub fn evaluate_seating(
                 if let Some(student) = students_map.get(student_name) {
                     let wishes = wishes_map.get(student_name.as_str()).unwrap();
                     let mut fulfilled = 0.0;
-                    if let Some(neighbor) = table.top.get(0).and_then(|s| s.as_ref()) {
+                    if let Some(neighbor) = table.top.first().and_then(|s| s.as_ref()) {
                         if wishes.contains(neighbor.as_str()) { fulfilled += 1.0; }
                     }
-                    if let Some(neighbor) = table.bottom.get(0).and_then(|s| s.as_ref()) {
+                    if let Some(neighbor) = table.bottom.first().and_then(|s| s.as_ref()) {
                         if wishes.contains(neighbor.as_str()) { fulfilled += 1.0; }
                     }
                     let base_score = fulfilled * student.weight;
@@ -256,17 +454,17 @@ pub fn is_perfect_seating(
                     let wishes = wishes_map.get(student_name.as_str()).unwrap();
                     let mut fulfilled = 0;
                     if i > 0 {
-                        if let Some(ref neighbor) = table.top[i - 1].as_ref() {
+                        if let Some(neighbor) = table.top[i - 1].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1; }
                         }
                     }
                     if i + 1 < top_len {
-                        if let Some(ref neighbor) = table.top[i + 1].as_ref() {
+                        if let Some(neighbor) = table.top[i + 1].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1; }
                         }
                     }
                     if i < bottom_len {
-                        if let Some(ref neighbor) = table.bottom[i].as_ref() {
+                        if let Some(neighbor) = table.bottom[i].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1; }
                         }
                     }
@@ -283,17 +481,17 @@ pub fn is_perfect_seating(
                     let wishes = wishes_map.get(student_name.as_str()).unwrap();
                     let mut fulfilled = 0;
                     if i > 0 {
-                        if let Some(ref neighbor) = table.bottom[i - 1].as_ref() {
+                        if let Some(neighbor) = table.bottom[i - 1].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1; }
                         }
                     }
                     if i + 1 < bottom_len {
-                        if let Some(ref neighbor) = table.bottom[i + 1].as_ref() {
+                        if let Some(neighbor) = table.bottom[i + 1].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1; }
                         }
                     }
                     if i < top_len {
-                        if let Some(ref neighbor) = table.top[i].as_ref() {
+                        if let Some(neighbor) = table.top[i].as_ref() {
                             if wishes.contains(neighbor.as_str()) { fulfilled += 1; }
                         }
                     }
@@ -308,10 +506,10 @@ pub fn is_perfect_seating(
             if let Some(student) = students_map.get(student_name) {
                 let wishes = wishes_map.get(student_name.as_str()).unwrap();
                 let mut fulfilled = 0;
-                if let Some(neighbor) = table.top.get(0).and_then(|s| s.as_ref()) {
+                if let Some(neighbor) = table.top.first().and_then(|s| s.as_ref()) {
                     if wishes.contains(neighbor.as_str()) { fulfilled += 1; }
                 }
-                if let Some(neighbor) = table.bottom.get(0).and_then(|s| s.as_ref()) {
+                if let Some(neighbor) = table.bottom.first().and_then(|s| s.as_ref()) {
                     if wishes.contains(neighbor.as_str()) { fulfilled += 1; }
                 }
                 if fulfilled == 0 && !student.wishes.is_empty() {
@@ -438,34 +636,16 @@ pub fn swap_seats(
     }
 }
 
-// --- Optimization Functions ---
-//
-// The optimization functions update the shared GLOBAL_PROGRESS state as they run.
-pub fn optimize_seating_simulated_annealing(
-    initial_arrangement: SeatingArrangement,
-    fixed_coords: Vec<Coordinate>,
-    students_map: HashMap<String, Student>,
-    bonus_parameter: f64,
+// Every seat the optimizers may move students in and out of: all seats in use for the bonus
+// configuration, minus the fixed ones.
+pub fn build_free_coords(
+    arrangement: &SeatingArrangement,
+    fixed_coords: &[Coordinate],
     bonus_config: &str,
-    iterations: usize,
-    initial_temperature: f64,
-    cooling_rate: f64,
-    early_stop: bool,
-    run_id: usize, // run identifier for logging
-) -> (SeatingArrangement, PerformanceLog) {
-    let start = Instant::now();
-    let mut current_arrangement = initial_arrangement.clone();
-    let mut best_arrangement = initial_arrangement.clone();
-    let wishes_map = build_wishes_map(&students_map);
-    let mut current_score = evaluate_seating(&current_arrangement, &students_map, &wishes_map, bonus_parameter, bonus_config);
-    let mut best_score = current_score;
-    let mut temperature = initial_temperature;
-    let mut log_messages = Vec::new();
-
-    // Build free coordinates.
-    let total_tables = current_arrangement.tables.len();
+) -> Vec<Coordinate> {
+    let total_tables = arrangement.tables.len();
     let mut free_coords = Vec::with_capacity(total_tables * 6);
-    for (t_idx, table) in current_arrangement.tables.iter().enumerate() {
+    for (t_idx, table) in arrangement.tables.iter().enumerate() {
         for i in 0..table.top.len() {
             let coord = Coordinate {
                 table: t_idx,
@@ -507,19 +687,80 @@ pub fn optimize_seating_simulated_annealing(
             }
         }
     }
+    free_coords
+}
+
+// --- Optimization Functions ---
+//
+// The optimization functions report progress to `options.job` as they run.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn optimize_seating_simulated_annealing(
+    initial_arrangement: SeatingArrangement,
+    fixed_coords: Vec<Coordinate>,
+    students_map: HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+    iterations: usize,
+    initial_temperature: f64,
+    cooling_rate: f64,
+    early_stop: bool,
+    options: &OptimizeOptions,
+    run_id: usize, // run identifier for logging
+) -> (SeatingArrangement, PerformanceLog) {
+    let start = Instant::now();
+    let mut current_arrangement = initial_arrangement.clone();
+    let mut best_arrangement = initial_arrangement.clone();
+    let wishes_map = build_wishes_map(&students_map);
+    let free_coords = build_free_coords(&current_arrangement, &fixed_coords, bonus_config);
+    // Only used in minimal-disruption mode, to price and count moves away from home seats, and
+    // with a fairness or lexicographic objective, which only the seat scorer knows how to score.
+    let by_seat = options.objective != Objective::Sum;
+    let disruption = (options.disruption.enabled() || by_seat).then(|| {
+        let scorer = Scorer::new(&current_arrangement, &students_map, bonus_parameter, bonus_config)
+            .with_disruption(&options.disruption)
+            .with_objective(options.objective);
+        let free_seats: Vec<usize> = free_coords.iter().filter_map(|c| scorer.graph.id_of(c)).collect();
+        (scorer, free_seats)
+    });
+    // Satisfied students are only counted under the lexicographic objective; otherwise they stay 0.
+    let initial = match &disruption {
+        Some((scorer, _)) if by_seat => scorer.total_ranked(&current_arrangement),
+        _ => Ranked {
+            satisfied: 0,
+            score: evaluate_seating(&current_arrangement, &students_map, &wishes_map, bonus_parameter, bonus_config),
+        },
+    };
+    let max_satisfied = disruption.as_ref().and_then(|(scorer, _)| max_satisfied(scorer, &current_arrangement, options.objective));
+    let mut current_score = initial.score;
+    let mut current_satisfied = initial.satisfied;
+    let mut best_score = current_score;
+    let mut best_satisfied = current_satisfied;
+    let mut temperature = initial_temperature;
+    let mut log_messages = Vec::new();
+    let mut acceptor = Acceptor::new(&options.acceptance, initial, iterations, &students_map, bonus_parameter, options.objective);
+    let mut moved = 0usize;
+    let mut cooler = Cooler::new(&options.cooling, initial_temperature, cooling_rate, iterations);
+    let mut trace = Trace::new(&options.trace);
+    let mut iterations_done = 0;
+    let mut early_stop_iteration = None;
 
     let mut rng = thread_rng();
 
     // Main simulated annealing loop.
     for iter in 0..iterations {
         if free_coords.len() < 2 { break; }
+        if trace.due(iter) {
+            trace.record(iter, best_score, current_score, temperature, &acceptor.stats);
+        }
         if iter % 10_000 == 0 {
-            if let Ok(mut prog) = GLOBAL_PROGRESS.lock() {
-                prog.iteration = iter;
-                prog.best_score = best_score;
-                prog.temperature = temperature;
-            }
+            report_progress(options, iter, best_score, temperature);
+            report_snapshot(options, &best_arrangement, best_score);
+        }
+        if iter % 1_000 == 0 && is_cancelled(options) {
+            log_messages.push(format!("Run {}: Cancelled at iteration {}", run_id, iter));
+            break;
         }
+        iterations_done = iter + 1;
         let len = free_coords.len();
         let idx1 = rng.gen_range(0..len);
         let mut idx2 = rng.gen_range(0..len);
@@ -528,92 +769,144 @@ pub fn optimize_seating_simulated_annealing(
         }
         let coord1 = free_coords[idx1].clone();
         let coord2 = free_coords[idx2].clone();
-
-        let old_local_score = if coord1.table == coord2.table {
-            evaluate_table(&current_arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-        } else {
-            evaluate_table(&current_arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-            + evaluate_table(&current_arrangement.tables[coord2.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
+        let local_score = |arrangement: &SeatingArrangement| match &disruption {
+            // Seat scores already include the objective and the move costs.
+            Some((scorer, seats)) if by_seat => scorer.local_ranked(arrangement, &[seats[idx1], seats[idx2]]),
+            _ if coord1.table == coord2.table => Ranked {
+                satisfied: 0,
+                score: evaluate_table(&arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config),
+            },
+            _ => Ranked {
+                satisfied: 0,
+                score: evaluate_table(&arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
+                    + evaluate_table(&arrangement.tables[coord2.table], &students_map, &wishes_map, bonus_parameter, bonus_config),
+            },
         };
 
+        let old_local_score = local_score(&current_arrangement);
+
+        let moved_before = disruption.as_ref().map_or(0, |(scorer, seats)| {
+            scorer.moved(&current_arrangement, seats[idx1]) as usize + scorer.moved(&current_arrangement, seats[idx2]) as usize
+        });
         swap_seats(&mut current_arrangement, &coord1, &coord2);
+        let moved_after = disruption.as_ref().map_or(0, |(scorer, seats)| {
+            scorer.moved(&current_arrangement, seats[idx1]) as usize + scorer.moved(&current_arrangement, seats[idx2]) as usize
+        });
+        let candidate_moved = moved + moved_after - moved_before;
+
+        let new_local_score = local_score(&current_arrangement);
 
-        let new_local_score = if coord1.table == coord2.table {
-            evaluate_table(&current_arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
+        let mut delta = new_local_score - old_local_score;
+        if !by_seat {
+            delta.score -= (moved_after as f64 - moved_before as f64) * options.disruption.move_cost;
+        }
+        let within_cap = options.disruption.max_moves.is_none_or(|max| candidate_moved <= max);
+        // The acceptance rule weighs changes in satisfied students against the score itself.
+        let current = Ranked { satisfied: current_satisfied, score: current_score };
+        let accepted = if within_cap {
+            acceptor.accept(iter, current, delta, temperature, &mut rng)
         } else {
-            evaluate_table(&current_arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-            + evaluate_table(&current_arrangement.tables[coord2.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
+            acceptor.reject(iter, current);
+            false
         };
-
-        let delta = new_local_score - old_local_score;
-        let candidate_score = current_score + delta;
-        if delta >= 0.0 || rng.gen_bool((delta / temperature).exp().min(1.0)) {
-            current_score = candidate_score;
-            if current_score > best_score {
+        if accepted {
+            current_score += delta.score;
+            current_satisfied += delta.satisfied;
+            moved = candidate_moved;
+            let current = Ranked { satisfied: current_satisfied, score: current_score };
+            if current.beats(Ranked { satisfied: best_satisfied, score: best_score }, 0.0) {
                 best_arrangement = current_arrangement.clone();
                 best_score = current_score;
-                if early_stop && is_perfect_seating(&best_arrangement, &students_map, &wishes_map) {
+                best_satisfied = current_satisfied;
+                if early_stop && is_best_possible(&best_arrangement, best_satisfied, max_satisfied, &students_map, &wishes_map) {
                     log_messages.push(format!("Run {}: Early stopping at iteration {}", run_id, iter));
+                    early_stop_iteration = Some(iter);
                     break;
                 }
             }
         } else {
             swap_seats(&mut current_arrangement, &coord1, &coord2);
         }
-        temperature *= cooling_rate;
-        if temperature < 1e-8 {
-            temperature = 1e-8;
-        }
+        temperature = cooler.next();
     }
+    let trace = trace.finish(iterations_done, best_score, current_score, temperature, &acceptor.stats);
 
     // Local search phase.
-    let local_search_start = Instant::now();
-    let mut improvement = true;
-    while improvement {
-        improvement = false;
-        for i in 0..free_coords.len() {
-            for j in (i+1)..free_coords.len() {
-                let coord1 = free_coords[i].clone();
-                let coord2 = free_coords[j].clone();
-                let old_local_score = if coord1.table == coord2.table {
-                    evaluate_table(&best_arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-                } else {
-                    evaluate_table(&best_arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-                    + evaluate_table(&best_arrangement.tables[coord2.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-                };
-                swap_seats(&mut best_arrangement, &coord1, &coord2);
-                let new_local_score = if coord1.table == coord2.table {
-                    evaluate_table(&best_arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-                } else {
-                    evaluate_table(&best_arrangement.tables[coord1.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-                    + evaluate_table(&best_arrangement.tables[coord2.table], &students_map, &wishes_map, bonus_parameter, bonus_config)
-                };
-                if new_local_score > old_local_score {
-                    best_score += new_local_score - old_local_score;
-                    improvement = true;
-                } else {
-                    swap_seats(&mut best_arrangement, &coord1, &coord2);
-                }
-            }
-        }
-    }
-    let local_search_time = local_search_start.elapsed();
+    let (_, local_search_stats) = local_search_phase(
+        &mut best_arrangement,
+        &free_coords,
+        &students_map,
+        bonus_parameter,
+        bonus_config,
+        options,
+        run_id,
+        &mut log_messages,
+    );
     let optimization_time = start.elapsed();
-    log_messages.push(format!("Run {}: Local search completed in {:?}", run_id, local_search_time));
+    log_messages.push(format!(
+        "Run {}: {:?} acceptance: {} improving, {} worse accepted, {} rejected (ratio {:.3})",
+        run_id,
+        options.acceptance.rule,
+        acceptor.stats.improving,
+        acceptor.stats.accepted_worse,
+        acceptor.stats.rejected,
+        acceptor.stats.acceptance_ratio()
+    ));
     log_messages.push(format!("Run {}: Total optimization time: {:?}", run_id, optimization_time));
     let log_summary = log_messages.join("\n");
     let perf_log = PerformanceLog {
         run_id,
-        total_iterations: iterations,
-        best_score,
+        total_iterations: iterations_done,
         optimization_time,
-        local_search_time,
+        local_search: local_search_stats,
+        acceptance: acceptor.stats,
+        trace,
+        early_stop_iteration,
+        moves_evaluated: iterations_done,
         log_summary,
     };
     (best_arrangement, perf_log)
 }
 
-pub fn parallel_annealing_search( 
+// Polishes the best arrangement of a run with local search over its free seats, then sends
+// students home if minimal-disruption mode caps the number of moves.
+#[allow(clippy::too_many_arguments)]
+fn local_search_phase(
+    arrangement: &mut SeatingArrangement,
+    free_coords: &[Coordinate],
+    students_map: &HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+    options: &OptimizeOptions,
+    run_id: usize,
+    log_messages: &mut Vec<String>,
+) -> (f64, LocalSearchStats) {
+    let scorer = Scorer::new(arrangement, students_map, bonus_parameter, bonus_config)
+        .with_disruption(&options.disruption)
+        .with_objective(options.objective);
+    let free_seats: Vec<usize> = free_coords.iter().filter_map(|c| scorer.graph.id_of(c)).collect();
+    let mut local_search_options = options.local_search.clone();
+    local_search_options.cancel = options.job.as_ref().map(|job| job.cancel_flag());
+    let (mut gained, stats) = local_search(arrangement, &free_seats, &scorer, &local_search_options);
+    if let Some(max_moves) = options.disruption.max_moves {
+        gained += disruption::enforce_max_moves(arrangement, &scorer, &free_seats, max_moves, local_search_options.cancel.as_deref());
+    }
+    log_messages.push(format!(
+        "Run {}: Local search completed in {:?} ({} improvements, {} evaluations{})",
+        run_id,
+        stats.time,
+        stats.improvements(),
+        stats.evaluations,
+        if stats.cancelled { ", cancelled" } else if stats.timed_out { ", time limit reached" } else { "" }
+    ));
+    (gained, stats)
+}
+
+// A kept run: its best arrangement, that arrangement's score and the run's log.
+type RunResult = (SeatingArrangement, f64, PerformanceLog);
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn parallel_annealing_search(
     initial_arrangement: SeatingArrangement,
     fixed_coords: Vec<Coordinate>,
     students_map: HashMap<String, Student>,
@@ -624,69 +917,237 @@ pub fn parallel_annealing_search(
     cooling_rate: f64,
     early_stop: bool,
     num_runs: usize, // e.g., 12 for a 12-core machine
-) -> SeatingArrangement {
+    options: &OptimizeOptions,
+) -> Result<(Vec<RunResult>, Vec<RunTelemetry>), String> {
+    // The genetic algorithm breeds a single population on `num_runs` worker threads instead of
+    // running independent copies.
+    let mut options = options.clone();
+    let runs = if options.algorithm == Algorithm::Genetic {
+        options.genetic.threads = num_runs;
+        1
+    } else {
+        num_runs
+    };
+    if options.disruption.enabled() {
+        options.disruption.home = Some(initial_arrangement.clone());
+    }
     let (tx, rx) = channel();
-    for run_id in 0..num_runs {
+    for run_id in 0..runs {
         let init_arr = initial_arrangement.clone();
         let fixed = fixed_coords.clone();
         let stud_map = students_map.clone();
         let bonus_config = bonus_config.to_string();
+        let options = options.clone();
         let tx = tx.clone();
         thread::spawn(move || {
-            let result = optimize_seating_simulated_annealing(
-                init_arr,
-                fixed,
-                stud_map.clone(),
-                bonus_parameter,
-                &bonus_config,
-                iterations,
-                initial_temperature,
-                cooling_rate,
-                early_stop,
-                run_id,
-            );
+            let result = match options.algorithm {
+                Algorithm::Annealing | Algorithm::Lns => {
+                    let optimize = match options.algorithm {
+                        Algorithm::Lns => lns::optimize_seating_lns,
+                        _ => optimize_seating_simulated_annealing,
+                    };
+                    optimize(
+                        init_arr,
+                        fixed,
+                        stud_map,
+                        bonus_parameter,
+                        &bonus_config,
+                        iterations,
+                        initial_temperature,
+                        cooling_rate,
+                        early_stop,
+                        &options,
+                        run_id,
+                    )
+                }
+                // Tabu search has no temperature.
+                Algorithm::Tabu => tabu::optimize_seating_tabu(
+                    init_arr,
+                    fixed,
+                    stud_map,
+                    bonus_parameter,
+                    &bonus_config,
+                    iterations,
+                    early_stop,
+                    &options,
+                    run_id,
+                ),
+                // The genetic algorithm runs `genetic.generations` generations instead.
+                Algorithm::Genetic => genetic::optimize_seating_genetic(
+                    init_arr,
+                    fixed,
+                    stud_map,
+                    bonus_parameter,
+                    &bonus_config,
+                    early_stop,
+                    &options,
+                    run_id,
+                ),
+            };
             tx.send(result).unwrap();
         });
     }
     drop(tx);
 
-    let wishes_map = build_wishes_map(&students_map);
-    let mut best_overall = None;
-    let mut best_score = f64::MIN;
-    let mut aggregated_logs = Vec::new();
+    let scorer = Scorer::new(&initial_arrangement, &students_map, bonus_parameter, bonus_config)
+        .with_disruption(&options.disruption)
+        .with_objective(options.objective);
+    let mut results = Vec::new();
+    let mut telemetry = Vec::new();
 
     for (arrangement, log) in rx.iter() {
-        aggregated_logs.push(log.log_summary);
-        let score = evaluate_seating(&arrangement, &students_map, &wishes_map, bonus_parameter, &bonus_config);
-        if score > best_score {
-            best_score = score;
-            best_overall = Some(arrangement);
-        }
+        let ranked = scorer.total_ranked(&arrangement);
+        let score = ranked.score;
+        let run = RunTelemetry::new(&log, options.algorithm, score);
+        log::info!(
+            "Run {}: score {:.2} after {} {}{}; search {:.0} ms ({:.0} moves/s), local search {:.0} ms{}",
+            run.run_id,
+            run.score,
+            run.iterations,
+            run.iteration_unit,
+            run.early_stop_iteration.map_or(String::new(), |i| format!(" (early stop at {})", i)),
+            run.search_time_ms,
+            run.moves_per_second,
+            run.local_search_time_ms,
+            run.acceptance_ratio.map_or(String::new(), |r| format!(", acceptance ratio {:.3}", r)),
+        );
+        log::debug!("{}", log.log_summary);
+        telemetry.push(run);
+        results.push((ranked, (arrangement, score, log)));
     }
-    println!("--- Parallel Annealing Summary ---");
-    for log in aggregated_logs {
-        println!("{}", log);
-        println!("----------------------------------");
+    // Runs that panicked send nothing.
+    if results.is_empty() {
+        return Err("No run produced a result".to_string());
     }
-    println!("Best overall score: {}", best_score);
-    best_overall.expect("At least one run should produce a result")
+    results.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let results: Vec<RunResult> = results.into_iter().map(|(_, result)| result).collect();
+    telemetry.sort_by_key(|run| run.run_id);
+    let alternatives = select_alternatives(results, &options.alternatives, bonus_config);
+    log::info!("Best overall score: {} ({} alternatives kept)", alternatives[0].1, alternatives.len());
+    Ok((alternatives, telemetry))
+}
+
+// Walks the run results from best to worst and keeps each one that differs from every result
+// kept so far in at least `min_distance` students' seats. The best result is always kept.
+fn select_alternatives(
+    results: Vec<RunResult>,
+    options: &AlternativesOptions,
+    bonus_config: &str,
+) -> Vec<RunResult> {
+    let graph = SeatGraph::new(&results[0].0, bonus_config);
+    let mut kept: Vec<RunResult> = Vec::new();
+    for result in results {
+        if kept.len() == options.count.max(1) {
+            break;
+        }
+        if kept.iter().all(|k| graph.distance(&k.0, &result.0) >= options.min_distance.max(1)) {
+            kept.push(result);
+        }
+    }
+    kept
 }
 
 // --- Neon API Functions ---
 //
-// optimizeSeating spawns a background thread to run the optimization. When done, it stores
-// the final result (as a JSON string) in GLOBAL_PROGRESS.final_result.
-fn optimize_seating_neon(mut cx: FunctionContext) -> JsResult<JsUndefined> {
-    let initial_arrangement_json = cx.argument::<JsString>(0)?.value();
-    let fixed_coords_json = cx.argument::<JsString>(1)?.value();
-    let students_map_json = cx.argument::<JsString>(2)?.value();
-    let bonus_parameter = cx.argument::<JsNumber>(3)?.value();
-    let bonus_config = cx.argument::<JsString>(4)?.value();
-    let iterations = cx.argument::<JsNumber>(5)?.value() as u32;
-    let initial_temperature = cx.argument::<JsNumber>(6)?.value();
-    let cooling_rate = cx.argument::<JsNumber>(7)?.value();
-    let early_stop = cx.argument::<JsBoolean>(8)?.value();
-    let parallel_runs = cx.argument::<JsNumber>(9)?.value() as usize;
+// optimizeSeating starts a job, runs the optimization on a background thread and returns the job
+// id. getProgress(jobId) polls the job; getResult(jobId) collects the final result (as a JSON
+// string) once it is done, which also removes the job. listJobs() lists all uncollected jobs.
+// Jobs that fail validateSeating are rejected with an error before any thread starts.
+//
+// optimizeSeatingAsync starts the same job but returns a Promise instead. The worker thread
+// settles it through a Channel with the parsed result and collects the job itself, and an
+// optional onProgress callback is called through the same Channel while the job runs.
+fn optimize_seating_neon(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let request = parse_optimization(&mut cx)?;
+    let job_id = start_optimization(request, None, |_| {});
+    Ok(cx.number(job_id as f64))
+}
+
+// Default time between two onProgress calls.
+const PROGRESS_INTERVAL_MS: f64 = 500.0;
+
+// optimizeSeatingAsync(...optimizeSeating arguments, onProgress, progressIntervalMs). The
+// returned Promise carries the job id as `jobId`, for cancelOptimization.
+fn optimize_seating_async_neon(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let request = parse_optimization(&mut cx)?;
+    let channel = cx.channel();
+    let on_progress = match cx.argument_opt(11) {
+        Some(arg) if arg.is_a::<JsFunction, _>(&mut cx) => Some(cx.argument::<JsFunction>(11)?.root(&mut cx)),
+        _ => None,
+    };
+    let interval_ms = match cx.argument_opt(12) {
+        Some(arg) if arg.is_a::<JsNumber, _>(&mut cx) => cx.argument::<JsNumber>(12)?.value(&mut cx),
+        _ => PROGRESS_INTERVAL_MS,
+    };
+    let progress_sink = on_progress.map(|callback| {
+        let callback = Arc::new(callback);
+        let channel = channel.clone();
+        job::ProgressSink::new(interval_ms.max(0.0) as u64, move |event| {
+            let callback = callback.clone();
+            let event_json = serde_json::to_string(&event).unwrap();
+            channel.send(move |mut cx| {
+                let event = parse_json(&mut cx, &event_json)?;
+                callback.to_inner(&mut cx).call_with(&cx).arg(event).exec(&mut cx)
+            });
+        })
+    });
+
+    let (deferred, promise) = cx.promise();
+    let job_id = start_optimization(request, progress_sink, move |job| {
+        // Nobody is meant to call getResult for this job, so collect it here. If the run failed, or
+        // JS did collect it first, the Promise is rejected rather than left pending.
+        let error = match job::take_result(job.id) {
+            Some(Ok(result)) => {
+                deferred.settle_with(&channel, move |mut cx| parse_json(&mut cx, &result));
+                return;
+            }
+            Some(Err(error)) => error,
+            None => format!("The result of job {} was already collected with getResult", job.id),
+        };
+        deferred.settle_with(&channel, move |mut cx| cx.throw_error::<_, Handle<JsValue>>(error));
+    });
+    let job_id = cx.number(job_id as f64);
+    promise.set(&mut cx, "jobId", job_id)?;
+    Ok(promise)
+}
+
+// Turns a JSON string built on the Rust side into a JS value, with the engine's JSON.parse.
+fn parse_json<'a, C: Context<'a>>(cx: &mut C, json: &str) -> JsResult<'a, JsValue> {
+    let json_object: Handle<JsObject> = cx.global().get(cx, "JSON")?;
+    let parse: Handle<JsFunction> = json_object.get(cx, "parse")?;
+    let text = cx.string(json);
+    parse.call_with(cx).arg(text).apply(cx)
+}
+
+// An optimizeSeating call, parsed and validated but not started yet.
+struct OptimizationRequest {
+    initial_arrangement: SeatingArrangement,
+    fixed_coords: Vec<Coordinate>,
+    students_map: HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: String,
+    iterations: usize,
+    initial_temperature: f64,
+    cooling_rate: f64,
+    early_stop: bool,
+    parallel_runs: usize,
+    options: OptimizeOptions,
+}
+
+// Parses the ten optimizeSeating arguments and the options at index 10, and throws if the job
+// fails validateSeating.
+fn parse_optimization(cx: &mut FunctionContext) -> NeonResult<OptimizationRequest> {
+    let initial_arrangement_json = cx.argument::<JsString>(0)?.value(cx);
+    let fixed_coords_json = cx.argument::<JsString>(1)?.value(cx);
+    let students_map_json = cx.argument::<JsString>(2)?.value(cx);
+    let bonus_parameter = cx.argument::<JsNumber>(3)?.value(cx);
+    let bonus_config = cx.argument::<JsString>(4)?.value(cx);
+    let iterations = cx.argument::<JsNumber>(5)?.value(cx) as u32;
+    let initial_temperature = cx.argument::<JsNumber>(6)?.value(cx);
+    let cooling_rate = cx.argument::<JsNumber>(7)?.value(cx);
+    let early_stop = cx.argument::<JsBoolean>(8)?.value(cx);
+    let parallel_runs = cx.argument::<JsNumber>(9)?.value(cx) as usize;
+    let options = parse_options(cx, 10)?;
 
     let initial_arrangement: SeatingArrangement = serde_json::from_str(&initial_arrangement_json)
         .or_else(|e| cx.throw_error(format!("Failed to parse initial_arrangement: {:?}", e)))?;
@@ -694,54 +1155,439 @@ fn optimize_seating_neon(mut cx: FunctionContext) -> JsResult<JsUndefined> {
         .or_else(|e| cx.throw_error(format!("Failed to parse fixed_coords: {:?}", e)))?;
     let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
         .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
-    {
-        let mut prog = GLOBAL_PROGRESS.lock().unwrap();
-        *prog = ProgressInfo {
-            iteration: 0,
-            best_score: std::f64::MIN,
-            temperature: initial_temperature, // start with the passed initial temperature
-            final_result: None,
-        };
+    let problems = validate::validate(&initial_arrangement, &fixed_coords, &students_map, &bonus_config);
+    if !problems.is_empty() {
+        let messages: Vec<&str> = problems.iter().map(|p| p.message.as_str()).collect();
+        return cx.throw_error(format!("Invalid job: {}", messages.join("; ")));
+    }
+    Ok(OptimizationRequest {
+        initial_arrangement,
+        fixed_coords,
+        students_map,
+        bonus_parameter,
+        bonus_config,
+        iterations: iterations as usize,
+        initial_temperature,
+        cooling_rate,
+        early_stop,
+        parallel_runs,
+        options,
+    })
+}
+
+// Registers a job for `request` with `progress_sink` and runs it on a background thread. Returns
+// the job id. `on_finish` runs on that thread once the job's result is stored.
+fn start_optimization(
+    request: OptimizationRequest,
+    progress_sink: Option<job::ProgressSink>,
+    on_finish: impl FnOnce(&job::Job) + Send + 'static,
+) -> u64 {
+    let OptimizationRequest {
+        initial_arrangement,
+        fixed_coords,
+        students_map,
+        bonus_parameter,
+        bonus_config,
+        iterations,
+        initial_temperature,
+        cooling_rate,
+        early_stop,
+        parallel_runs,
+        mut options,
+    } = request;
+    let job = job::start(initial_temperature);
+    let job_id = job.id;
+    if let Some(sink) = progress_sink {
+        job.set_progress_sink(sink);
     }
-    // Spawn a background thread to run the optimization.
+    options.job = Some(job.clone());
+    let home = options.disruption.enabled().then(|| initial_arrangement.clone());
+    // Spawn a background thread to run the optimization. A panic in the search still finishes the
+    // job, with the panic message as its error, so getResult and the Promise see it.
     thread::spawn(move || {
-        let best_arrangement = parallel_annealing_search(
-            initial_arrangement,
-            fixed_coords,
-            students_map.clone(),
-            bonus_parameter,
-            &bonus_config,
-            iterations as usize,
-            initial_temperature,
-            cooling_rate,
-            early_stop,
-            parallel_runs,
-        );
-        let wishes_map = build_wishes_map(&students_map);
-        let best_score = evaluate_seating(&best_arrangement, &students_map, &wishes_map, bonus_parameter, &bonus_config);
-        let result_obj = json!({
-            "seatingArrangement": best_arrangement,
-            "bestScore": best_score,
-        });
-        let result_json = serde_json::to_string(&result_obj).unwrap();
-        if let Ok(mut prog) = GLOBAL_PROGRESS.lock() {
-            prog.final_result = Some(result_json);
-        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let (alternatives, telemetry) = parallel_annealing_search(
+                initial_arrangement,
+                fixed_coords,
+                students_map.clone(),
+                bonus_parameter,
+                &bonus_config,
+                iterations,
+                initial_temperature,
+                cooling_rate,
+                early_stop,
+                parallel_runs,
+                &options,
+            )?;
+            Ok(result_json(&alternatives, &telemetry, home.as_ref(), &students_map, &options, &bonus_config))
+        }))
+        .unwrap_or_else(|payload| Err(format!("The optimisation panicked: {}", panic_message(&*payload))));
+        job.finish(result);
+        on_finish(&job);
     });
-    Ok(cx.undefined())
+    job_id
+}
+
+// The message of a caught panic, if it was given one.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message")
+}
+
+// The final result of a job: the best result at the top level, as before, and all kept results,
+// best first, under `alternatives`. `runs` has the telemetry of every run.
+fn result_json(
+    alternatives: &[RunResult],
+    telemetry: &[RunTelemetry],
+    home: Option<&SeatingArrangement>,
+    students_map: &HashMap<String, Student>,
+    options: &OptimizeOptions,
+    bonus_config: &str,
+) -> String {
+    let graph = SeatGraph::new(&alternatives[0].0, bonus_config);
+    let summaries: Vec<serde_json::Value> = alternatives
+        .iter()
+        .map(|(arrangement, score, log)| {
+            let mut summary = run_summary(arrangement, *score, log, options);
+            summary["distanceToBest"] = json!(graph.distance(&alternatives[0].0, arrangement));
+            summary["fairness"] = json!(statistics::fairness_of(arrangement, students_map, bonus_config));
+            if let Some(home) = home {
+                let moves = graph.distance(home, arrangement);
+                summary["disruption"] = json!({
+                    "moves": moves,
+                    "movePenalty": moves as f64 * options.disruption.move_cost,
+                });
+            }
+            summary
+        })
+        .collect();
+    let mut result_obj = summaries[0].clone();
+    result_obj["alternatives"] = serde_json::Value::Array(summaries);
+    result_obj["objective"] = json!(format!("{:?}", options.objective));
+    result_obj["runs"] = json!(telemetry);
+    result_obj["cancelled"] = json!(is_cancelled(options));
+    serde_json::to_string(&result_obj).unwrap()
+}
+
+// Score and statistics of one run's result, as reported to JS.
+fn run_summary(arrangement: &SeatingArrangement, score: f64, log: &PerformanceLog, options: &OptimizeOptions) -> serde_json::Value {
+    json!({
+        "seatingArrangement": arrangement,
+        "bestScore": score,
+        "runId": log.run_id,
+        "annealingTimeMs": (log.optimization_time - log.local_search.time).as_secs_f64() * 1000.0,
+        "localSearch": {
+            "timeMs": log.local_search.time.as_secs_f64() * 1000.0,
+            "improvements": log.local_search.improvements(),
+            "twoSwapImprovements": log.local_search.two_swap_improvements,
+            "threeSwapImprovements": log.local_search.three_swap_improvements,
+            "evaluations": log.local_search.evaluations,
+            "timedOut": log.local_search.timed_out,
+            "cancelled": log.local_search.cancelled,
+        },
+        "acceptance": {
+            "rule": format!("{:?}", options.acceptance.rule),
+            "improving": log.acceptance.improving,
+            "acceptedWorse": log.acceptance.accepted_worse,
+            "rejected": log.acceptance.rejected,
+            "acceptanceRatio": log.acceptance.acceptance_ratio(),
+        },
+        "trace": log.trace,
+    })
 }
 
-// getProgress returns the current progress (and final result, if available) as a JS string (JSON).
+// Reads the optional options JSON string at `index`; missing or undefined means all defaults.
+fn parse_options(cx: &mut FunctionContext, index: i32) -> NeonResult<OptimizeOptions> {
+    match cx.argument_opt(index) {
+        Some(arg) if arg.is_a::<JsString, _>(cx) => {
+            let options_json = cx.argument::<JsString>(index)?.value(cx);
+            serde_json::from_str(&options_json)
+                .or_else(|e| cx.throw_error(format!("Failed to parse options: {:?}", e)))
+        }
+        _ => Ok(OptimizeOptions::default()),
+    }
+}
+
+// getProgress(jobId) returns the job's current progress as a JS string (JSON).
 fn get_progress(mut cx: FunctionContext) -> JsResult<JsValue> {
-    let prog = GLOBAL_PROGRESS.lock().unwrap();
-    let json_str = match serde_json::to_string(&*prog) {
+    let job_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    let job = match job::get(job_id) {
+        Some(job) => job,
+        None => return cx.throw_error(format!("Unknown job {}", job_id)),
+    };
+    let json_str = match serde_json::to_string(&job.progress()) {
         Ok(s) => s,
         Err(e) => return cx.throw_error(format!("Failed to serialize progress: {:?}", e)),
     };
     Ok(cx.string(json_str).upcast())
 }
 
-register_module!(mut cx, {
-    cx.export_function("optimizeSeating", optimize_seating_neon);
-    cx.export_function("getProgress", get_progress)
-});
+// getResult(jobId) returns the job's final result as a JS string (JSON), or null while it is still
+// running, and throws if the run failed. The job is forgotten once its result has been returned.
+fn get_result(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let job_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    if job::get(job_id).is_none() {
+        return cx.throw_error(format!("Unknown job {}", job_id));
+    }
+    match job::take_result(job_id) {
+        Some(Ok(result)) => Ok(cx.string(result).upcast()),
+        Some(Err(error)) => cx.throw_error(format!("Job {} failed: {}", job_id, error)),
+        None => Ok(cx.null().upcast()),
+    }
+}
+
+// cancelOptimization(jobId) stops a running job. Its result is still delivered through getResult,
+// holding the best arrangement found so far and marked `cancelled`. Returns false if the job had
+// already finished.
+fn cancel_optimization(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let job_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    match job::get(job_id) {
+        Some(job) => Ok(cx.boolean(job.cancel())),
+        None => cx.throw_error(format!("Unknown job {}", job_id)),
+    }
+}
+
+// listJobs() returns every job whose result has not been collected yet as a JS string (JSON).
+fn list_jobs(mut cx: FunctionContext) -> JsResult<JsString> {
+    match serde_json::to_string(&job::list()) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize jobs: {:?}", e)),
+    }
+}
+
+// diffSeating(before, after, studentsMap, bonusParameter, bonusConfig) compares two arrangements
+// of the same room and returns the differences as a JSON string.
+fn diff_seating_neon(mut cx: FunctionContext) -> JsResult<JsString> {
+    let before_json = cx.argument::<JsString>(0)?.value(&mut cx);
+    let after_json = cx.argument::<JsString>(1)?.value(&mut cx);
+    let students_map_json = cx.argument::<JsString>(2)?.value(&mut cx);
+    let bonus_parameter = cx.argument::<JsNumber>(3)?.value(&mut cx);
+    let bonus_config = cx.argument::<JsString>(4)?.value(&mut cx);
+
+    let before: SeatingArrangement = serde_json::from_str(&before_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse first arrangement: {:?}", e)))?;
+    let after: SeatingArrangement = serde_json::from_str(&after_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse second arrangement: {:?}", e)))?;
+    let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
+    if !diff::same_shape(&before, &after) {
+        return cx.throw_error("Arrangements must have the same tables and row lengths");
+    }
+    let seating_diff = diff::diff_seating(&before, &after, &students_map, bonus_parameter, &bonus_config);
+    match serde_json::to_string(&seating_diff) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize diff: {:?}", e)),
+    }
+}
+
+// explainSeating(arrangement, studentsMap, bonusParameter, bonusConfig) breaks the score down per
+// student and per table and returns it as a JSON string.
+fn explain_seating_neon(mut cx: FunctionContext) -> JsResult<JsString> {
+    let arrangement_json = cx.argument::<JsString>(0)?.value(&mut cx);
+    let students_map_json = cx.argument::<JsString>(1)?.value(&mut cx);
+    let bonus_parameter = cx.argument::<JsNumber>(2)?.value(&mut cx);
+    let bonus_config = cx.argument::<JsString>(3)?.value(&mut cx);
+
+    let arrangement: SeatingArrangement = serde_json::from_str(&arrangement_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse arrangement: {:?}", e)))?;
+    let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
+    let explanation = explain::explain_seating(&arrangement, &students_map, bonus_parameter, &bonus_config);
+    match serde_json::to_string(&explanation) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize explanation: {:?}", e)),
+    }
+}
+
+// computeStatistics(arrangement, studentsMap, config) returns the wish fulfilment statistics as a
+// JSON string. config is a JSON object with bonusParameter and bonusConfig.
+fn compute_statistics_neon(mut cx: FunctionContext) -> JsResult<JsString> {
+    let arrangement_json = cx.argument::<JsString>(0)?.value(&mut cx);
+    let students_map_json = cx.argument::<JsString>(1)?.value(&mut cx);
+    let config_json = cx.argument::<JsString>(2)?.value(&mut cx);
+
+    let arrangement: SeatingArrangement = serde_json::from_str(&arrangement_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse arrangement: {:?}", e)))?;
+    let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
+    let config: statistics::StatisticsConfig = serde_json::from_str(&config_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse config: {:?}", e)))?;
+    let stats = statistics::compute_statistics(&arrangement, &students_map, &config);
+    match serde_json::to_string(&stats) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize statistics: {:?}", e)),
+    }
+}
+
+// validateSeating(arrangement, fixedCoords, studentsMap, bonusConfig) returns the problems that
+// make optimizeSeating reject a job, as a JSON array; empty if there are none.
+fn validate_seating_neon(mut cx: FunctionContext) -> JsResult<JsString> {
+    let arrangement_json = cx.argument::<JsString>(0)?.value(&mut cx);
+    let fixed_coords_json = cx.argument::<JsString>(1)?.value(&mut cx);
+    let students_map_json = cx.argument::<JsString>(2)?.value(&mut cx);
+    let bonus_config = cx.argument::<JsString>(3)?.value(&mut cx);
+
+    let arrangement: SeatingArrangement = serde_json::from_str(&arrangement_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse arrangement: {:?}", e)))?;
+    let fixed_coords: Vec<Coordinate> = serde_json::from_str(&fixed_coords_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse fixed_coords: {:?}", e)))?;
+    let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
+    let problems = validate::validate(&arrangement, &fixed_coords, &students_map, &bonus_config);
+    match serde_json::to_string(&problems) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize problems: {:?}", e)),
+    }
+}
+
+// analyzeWishes(studentsMap, [config]) checks the wishes before optimising and returns the report
+// as a JSON string. The optional config is a JSON object with seatsPerTable and bonusConfig.
+fn analyze_wishes_neon(mut cx: FunctionContext) -> JsResult<JsString> {
+    let students_map_json = cx.argument::<JsString>(0)?.value(&mut cx);
+    let config_json = match cx.argument_opt(1) {
+        Some(arg) if arg.is_a::<JsString, _>(&mut cx) => cx.argument::<JsString>(1)?.value(&mut cx),
+        _ => "{}".to_string(),
+    };
+
+    let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
+    let config: wishes::WishAnalysisConfig = serde_json::from_str(&config_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse config: {:?}", e)))?;
+    let analysis = wishes::analyze_wishes(&students_map, &config);
+    match serde_json::to_string(&analysis) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize wish analysis: {:?}", e)),
+    }
+}
+
+// suggestSwaps(arrangement, fixedCoords, studentsMap, bonusParameter, bonusConfig, k, [pinnedStudents])
+// returns the k swaps or moves into empty seats that would raise the score the most, as a JSON
+// string. Seats in fixedCoords and seats of the students named in the optional pinnedStudents
+// JSON array are left alone.
+fn suggest_swaps_neon(mut cx: FunctionContext) -> JsResult<JsString> {
+    let arrangement_json = cx.argument::<JsString>(0)?.value(&mut cx);
+    let fixed_coords_json = cx.argument::<JsString>(1)?.value(&mut cx);
+    let students_map_json = cx.argument::<JsString>(2)?.value(&mut cx);
+    let bonus_parameter = cx.argument::<JsNumber>(3)?.value(&mut cx);
+    let bonus_config = cx.argument::<JsString>(4)?.value(&mut cx);
+    let k = cx.argument::<JsNumber>(5)?.value(&mut cx) as usize;
+    let pinned_json = match cx.argument_opt(6) {
+        Some(arg) if arg.is_a::<JsString, _>(&mut cx) => cx.argument::<JsString>(6)?.value(&mut cx),
+        _ => "[]".to_string(),
+    };
+
+    let arrangement: SeatingArrangement = serde_json::from_str(&arrangement_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse arrangement: {:?}", e)))?;
+    let fixed_coords: Vec<Coordinate> = serde_json::from_str(&fixed_coords_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse fixed_coords: {:?}", e)))?;
+    let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
+    let pinned: HashSet<String> = serde_json::from_str(&pinned_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse pinned students: {:?}", e)))?;
+
+    let scorer = Scorer::new(&arrangement, &students_map, bonus_parameter, &bonus_config);
+    let free_seats: Vec<usize> = build_free_coords(&arrangement, &fixed_coords, &bonus_config)
+        .iter()
+        .filter_map(|c| scorer.graph.id_of(c))
+        .filter(|&s| scorer.graph.occupant(&arrangement, s).is_none_or(|name| !pinned.contains(name)))
+        .collect();
+    let suggestions = moves::suggest_swaps(&arrangement, &scorer, &free_seats, k);
+    match serde_json::to_string(&suggestions) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize suggestions: {:?}", e)),
+    }
+}
+
+// evaluateMove(arrangement, coordA, coordB, studentsMap, bonusParameter, bonusConfig) returns the
+// score change of swapping the two seats (or moving a student into an empty one) and the students
+// it affects, as a JSON string. Nothing is changed.
+fn evaluate_move_neon(mut cx: FunctionContext) -> JsResult<JsString> {
+    let arrangement_json = cx.argument::<JsString>(0)?.value(&mut cx);
+    let coord_a_json = cx.argument::<JsString>(1)?.value(&mut cx);
+    let coord_b_json = cx.argument::<JsString>(2)?.value(&mut cx);
+    let students_map_json = cx.argument::<JsString>(3)?.value(&mut cx);
+    let bonus_parameter = cx.argument::<JsNumber>(4)?.value(&mut cx);
+    let bonus_config = cx.argument::<JsString>(5)?.value(&mut cx);
+
+    let mut arrangement: SeatingArrangement = serde_json::from_str(&arrangement_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse arrangement: {:?}", e)))?;
+    let coord_a: Coordinate = serde_json::from_str(&coord_a_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse first coordinate: {:?}", e)))?;
+    let coord_b: Coordinate = serde_json::from_str(&coord_b_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse second coordinate: {:?}", e)))?;
+    let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
+
+    let scorer = Scorer::new(&arrangement, &students_map, bonus_parameter, &bonus_config);
+    let (a, b) = match (scorer.graph.id_of(&coord_a), scorer.graph.id_of(&coord_b)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return cx.throw_error("Coordinate does not name a seat in this arrangement"),
+    };
+    let evaluation = moves::evaluate_move(&mut arrangement, &scorer, a, b);
+    match serde_json::to_string(&evaluation) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize evaluation: {:?}", e)),
+    }
+}
+
+// addStudent / removeStudent(arrangement, fixedCoords, studentsMap, bonusParameter, bonusConfig, name)
+// seat or unseat one student, re-optimise only the affected table, and return the new arrangement
+// with its diff against the old one as a JSON string.
+fn add_student_neon(cx: FunctionContext) -> JsResult<JsString> {
+    roster_change_neon(cx, roster::add_student)
+}
+
+fn remove_student_neon(cx: FunctionContext) -> JsResult<JsString> {
+    roster_change_neon(cx, roster::remove_student)
+}
+
+type RosterChangeFn =
+    fn(&SeatingArrangement, &[Coordinate], &HashMap<String, Student>, f64, &str, &str) -> Result<roster::RosterChange, String>;
+
+fn roster_change_neon(mut cx: FunctionContext, change: RosterChangeFn) -> JsResult<JsString> {
+    let arrangement_json = cx.argument::<JsString>(0)?.value(&mut cx);
+    let fixed_coords_json = cx.argument::<JsString>(1)?.value(&mut cx);
+    let students_map_json = cx.argument::<JsString>(2)?.value(&mut cx);
+    let bonus_parameter = cx.argument::<JsNumber>(3)?.value(&mut cx);
+    let bonus_config = cx.argument::<JsString>(4)?.value(&mut cx);
+    let name = cx.argument::<JsString>(5)?.value(&mut cx);
+
+    let arrangement: SeatingArrangement = serde_json::from_str(&arrangement_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse arrangement: {:?}", e)))?;
+    let fixed_coords: Vec<Coordinate> = serde_json::from_str(&fixed_coords_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse fixed_coords: {:?}", e)))?;
+    let students_map: HashMap<String, Student> = serde_json::from_str(&students_map_json)
+        .or_else(|e| cx.throw_error(format!("Failed to parse students_map: {:?}", e)))?;
+    let result = change(&arrangement, &fixed_coords, &students_map, bonus_parameter, &bonus_config, &name)
+        .or_else(|e| cx.throw_error(e))?;
+    match serde_json::to_string(&result) {
+        Ok(s) => Ok(cx.string(s)),
+        Err(e) => cx.throw_error(format!("Failed to serialize result: {:?}", e)),
+    }
+}
+
+#[neon::main]
+fn main(mut cx: ModuleContext) -> NeonResult<()> {
+    // Sends the log crate's output to stderr, filtered by RUST_LOG. Loading the addon a second
+    // time keeps the logger of the first load.
+    let _ = env_logger::try_init();
+    cx.export_function("optimizeSeating", optimize_seating_neon)?;
+    cx.export_function("optimizeSeatingAsync", optimize_seating_async_neon)?;
+    cx.export_function("diffSeating", diff_seating_neon)?;
+    cx.export_function("explainSeating", explain_seating_neon)?;
+    cx.export_function("computeStatistics", compute_statistics_neon)?;
+    cx.export_function("validateSeating", validate_seating_neon)?;
+    cx.export_function("analyzeWishes", analyze_wishes_neon)?;
+    cx.export_function("suggestSwaps", suggest_swaps_neon)?;
+    cx.export_function("evaluateMove", evaluate_move_neon)?;
+    cx.export_function("addStudent", add_student_neon)?;
+    cx.export_function("removeStudent", remove_student_neon)?;
+    cx.export_function("getProgress", get_progress)?;
+    cx.export_function("getResult", get_result)?;
+    cx.export_function("cancelOptimization", cancel_optimization)?;
+    cx.export_function("listJobs", list_jobs)?;
+    Ok(())
+}
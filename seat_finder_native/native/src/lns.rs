@@ -0,0 +1,290 @@
+use crate::acceptance::AcceptanceStats;
+use crate::cooling::Cooler;
+use crate::disruption;
+use crate::local_search::{local_search, LocalSearchOptions, LocalSearchStrategy};
+use crate::scoring::Scorer;
+use crate::trace::Trace;
+use crate::{
+    build_free_coords, build_wishes_map, is_best_possible, is_cancelled, local_search_phase, max_satisfied,
+    report_progress, report_snapshot, Coordinate, OptimizeOptions, PerformanceLog, SeatingArrangement, Student,
+};
+use rand::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Instant;
+
+// --- Large Neighbourhood Search ---
+//
+// Each step unseats every student at a few tables ("destroy") and puts them back one by one into
+// the seat where they add the most score ("repair"), followed by a quick 2-opt over the rebuilt
+// seats. Rebuilding whole tables moves groups of friends together, which single swaps can only
+// do through a long chain of worse intermediate states. Under the lexicographic objective the
+// acceptance rules see `Ranked::weighted` scores, as in the annealer.
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum LnsAcceptance {
+    /// Metropolis rule, cooled with the configured cooling schedule.
+    Annealing,
+    /// Late-acceptance hill climbing: accept if no worse than the score `lateAcceptanceLength`
+    /// steps ago.
+    LateAcceptance,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LnsOptions {
+    pub steps: usize,
+    pub min_tables: usize,
+    pub max_tables: usize,
+    pub acceptance: LnsAcceptance,
+    pub late_acceptance_length: usize,
+}
+
+impl Default for LnsOptions {
+    fn default() -> Self {
+        LnsOptions {
+            steps: 5_000,
+            min_tables: 2,
+            max_tables: 3,
+            acceptance: LnsAcceptance::Annealing,
+            late_acceptance_length: 500,
+        }
+    }
+}
+
+// Time cap for the 2-opt run on the rebuilt seats after each repair.
+const REPAIR_POLISH_MS: u64 = 50;
+
+#[allow(clippy::too_many_arguments)]
+pub fn optimize_seating_lns(
+    initial_arrangement: SeatingArrangement,
+    fixed_coords: Vec<Coordinate>,
+    students_map: HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+    iterations: usize,
+    initial_temperature: f64,
+    cooling_rate: f64,
+    early_stop: bool,
+    options: &OptimizeOptions,
+    run_id: usize,
+) -> (SeatingArrangement, PerformanceLog) {
+    let start = Instant::now();
+    let lns_options = &options.lns;
+    let steps = lns_options.steps.max(1);
+    let mut current_arrangement = initial_arrangement.clone();
+    let mut best_arrangement = initial_arrangement;
+    let wishes_map = build_wishes_map(&students_map);
+    let scorer = Scorer::new(&current_arrangement, &students_map, bonus_parameter, bonus_config)
+        .with_disruption(&options.disruption)
+        .with_objective(options.objective);
+    let mut current = scorer.total_ranked(&current_arrangement);
+    let mut best = current;
+    let max_satisfied = max_satisfied(&scorer, &current_arrangement, options.objective);
+    let satisfied_weight = options.objective.satisfied_weight(&students_map, bonus_parameter);
+    let mut log_messages = Vec::new();
+
+    // Free seats grouped by table; only tables with at least one free seat can be destroyed.
+    let free_coords = build_free_coords(&current_arrangement, &fixed_coords, bonus_config);
+    let mut table_seats: Vec<Vec<usize>> = vec![Vec::new(); current_arrangement.tables.len()];
+    for coord in &free_coords {
+        if let Some(seat) = scorer.graph.id_of(coord) {
+            table_seats[coord.table].push(seat);
+        }
+    }
+    let tables: Vec<usize> = (0..table_seats.len()).filter(|&t| !table_seats[t].is_empty()).collect();
+
+    // The annealing rule ends at the same temperature the configured annealer would reach after
+    // `iterations` moves, spread over the (much fewer) LNS steps.
+    let step_cooling_rate = cooling_rate.powf(iterations as f64 / steps as f64);
+    let mut cooler = Cooler::new(&options.cooling, initial_temperature, step_cooling_rate, steps);
+    let mut temperature = cooler.temperature();
+    let mut history = vec![current.weighted(satisfied_weight); lns_options.late_acceptance_length.max(1)];
+    let polish_options = LocalSearchOptions {
+        strategy: LocalSearchStrategy::First,
+        time_limit_ms: REPAIR_POLISH_MS,
+        three_swap: false,
+        cancel: None,
+    };
+
+    let mut acceptance = AcceptanceStats::default();
+    let mut trace = Trace::new(&options.trace);
+
+    let mut rng = thread_rng();
+    let mut steps_done = 0;
+    let mut early_stop_iteration = None;
+    for step in 0..steps {
+        if tables.is_empty() {
+            break;
+        }
+        if is_cancelled(options) {
+            log_messages.push(format!("Run {}: Cancelled at LNS step {}", run_id, step));
+            break;
+        }
+        steps_done = step + 1;
+        if trace.due(step) {
+            trace.record(step * iterations / steps, best.score, current.score, temperature, &acceptance);
+        }
+        if step % 100 == 0 {
+            // Scaled to `iterations` so progress bars work the same for every algorithm.
+            report_progress(options, step * iterations / steps, best.score, temperature);
+        }
+        // Steps are slow enough to offer a snapshot after each one.
+        report_snapshot(options, &best_arrangement, best.score);
+
+        let max_tables = lns_options.max_tables.max(lns_options.min_tables).min(tables.len());
+        let min_tables = lns_options.min_tables.clamp(1, max_tables);
+        let count = rng.gen_range(min_tables..=max_tables);
+        let destroyed: Vec<usize> = tables
+            .choose_multiple(&mut rng, count)
+            .flat_map(|&t| table_seats[t].iter().copied())
+            .collect();
+
+        let before = scorer.local_ranked(&current_arrangement, &destroyed);
+        let saved: Vec<(usize, Option<String>)> = destroyed
+            .iter()
+            .map(|&seat| (seat, scorer.graph.seat_mut(&mut current_arrangement, seat).take()))
+            .collect();
+        let mut removed: Vec<String> = saved.iter().filter_map(|(_, name)| name.clone()).collect();
+        removed.shuffle(&mut rng);
+        repair(&mut current_arrangement, &scorer, &destroyed, removed, &mut rng);
+        local_search(&mut current_arrangement, &destroyed, &scorer, &polish_options);
+        // A rebuild that moves too many students away from home is sent back towards it, and
+        // rejected if that is not enough.
+        let within_cap = options.disruption.max_moves.is_none_or(|max| {
+            disruption::enforce_max_moves(&mut current_arrangement, &scorer, &destroyed, max, None);
+            disruption::count_moved(&current_arrangement, &scorer) <= max
+        });
+        let ranked_delta = scorer.local_ranked(&current_arrangement, &destroyed) - before;
+        let delta = ranked_delta.weighted(satisfied_weight);
+        let current_weighted = current.weighted(satisfied_weight);
+        let candidate_weighted = current_weighted + delta;
+
+        let accepted = match lns_options.acceptance {
+            _ if !within_cap => false,
+            LnsAcceptance::Annealing => delta >= 0.0 || rng.gen_bool((delta / temperature).exp().min(1.0)),
+            LnsAcceptance::LateAcceptance => {
+                let slot = step % history.len();
+                let accepted = candidate_weighted >= history[slot] || delta >= 0.0;
+                history[slot] = if accepted { candidate_weighted } else { current_weighted };
+                accepted
+            }
+        };
+        if !accepted {
+            acceptance.rejected += 1;
+        } else if delta >= 0.0 {
+            acceptance.improving += 1;
+        } else {
+            acceptance.accepted_worse += 1;
+        }
+        if accepted {
+            current += ranked_delta;
+            if current.beats(best, 0.0) {
+                best_arrangement = current_arrangement.clone();
+                best = current;
+                if early_stop && is_best_possible(&best_arrangement, best.satisfied, max_satisfied, &students_map, &wishes_map) {
+                    log_messages.push(format!("Run {}: Early stopping at LNS step {}", run_id, step));
+                    early_stop_iteration = Some(step);
+                    break;
+                }
+            }
+        } else {
+            for (seat, name) in saved {
+                *scorer.graph.seat_mut(&mut current_arrangement, seat) = name;
+            }
+        }
+        temperature = cooler.next();
+    }
+    let trace = trace.finish(steps_done * iterations / steps, best.score, current.score, temperature, &acceptance);
+
+    // Local search phase.
+    let (_, local_search_stats) = local_search_phase(
+        &mut best_arrangement,
+        &free_coords,
+        &students_map,
+        bonus_parameter,
+        bonus_config,
+        options,
+        run_id,
+        &mut log_messages,
+    );
+    let optimization_time = start.elapsed();
+    log_messages.push(format!("Run {}: LNS ran {} steps", run_id, steps_done));
+    log_messages.push(format!("Run {}: Total optimization time: {:?}", run_id, optimization_time));
+    let log_summary = log_messages.join("\n");
+    let perf_log = PerformanceLog {
+        run_id,
+        total_iterations: steps_done,
+        optimization_time,
+        local_search: local_search_stats,
+        acceptance,
+        trace,
+        early_stop_iteration,
+        moves_evaluated: steps_done,
+        log_summary,
+    };
+    (best_arrangement, perf_log)
+}
+
+/// Greedy insertion: each student in turn takes the empty seat among `seats` where they add the
+/// most score given everyone placed so far. Ties are broken at random.
+pub fn repair<R: Rng>(
+    arrangement: &mut SeatingArrangement,
+    scorer: &Scorer,
+    seats: &[usize],
+    students: Vec<String>,
+    rng: &mut R,
+) {
+    for name in students {
+        let mut best: Option<(f64, usize)> = None;
+        let mut ties = 0;
+        for &seat in seats {
+            if scorer.graph.occupant(arrangement, seat).is_some() {
+                continue;
+            }
+            let before = scorer.local_score(arrangement, &[seat]);
+            *scorer.graph.seat_mut(arrangement, seat) = Some(name.clone());
+            let gain = scorer.local_score(arrangement, &[seat]) - before;
+            *scorer.graph.seat_mut(arrangement, seat) = None;
+            match best {
+                Some((best_gain, _)) if gain < best_gain => {}
+                Some((best_gain, _)) if gain == best_gain => {
+                    ties += 1;
+                    if rng.gen_range(0..=ties) == 0 {
+                        best = Some((gain, seat));
+                    }
+                }
+                _ => {
+                    best = Some((gain, seat));
+                    ties = 0;
+                }
+            }
+        }
+        if let Some((_, seat)) = best {
+            *scorer.graph.seat_mut(arrangement, seat) = Some(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objective::Objective;
+    use crate::test_util::split_pair;
+
+    #[test]
+    fn late_acceptance_rebuilds_for_more_satisfied_students() {
+        let (room, students_map) = split_pair();
+        let options = OptimizeOptions {
+            objective: Objective::Lexicographic,
+            lns: LnsOptions { steps: 50, acceptance: LnsAcceptance::LateAcceptance, late_acceptance_length: 5, ..LnsOptions::default() },
+            ..OptimizeOptions::default()
+        };
+        let (best, log) = optimize_seating_lns(room.clone(), Vec::new(), students_map.clone(), 1.0, "none", 1_000, 1.0, 0.99, false, &options, 0);
+        // The search's own best, before the local search phase: H-Y and X-Z despite the lower score.
+        assert_eq!(log.trace.last().unwrap().best_score, 2.0);
+        let scorer = Scorer::new(&room, &students_map, 1.0, "none").with_objective(Objective::Lexicographic);
+        assert_eq!(scorer.total_ranked(&best).satisfied, 2);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::objective::Ranked;
+use crate::scoring::Scorer;
+use crate::SeatingArrangement;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// --- Local Search ---
+//
+// Polishes an arrangement with 2-opt (pairwise swaps) and, optionally, 3-cycles over the free
+// seats. Moves are scored with delta scoring against the seat graph, so a candidate costs a
+// handful of seat lookups instead of re-evaluating whole tables. The search stops when no move
+// improves or when the time limit is reached, whichever comes first. Moves are compared as
+// `Ranked` tuples, so under the lexicographic objective a move that satisfies one more student
+// wins over any change in weighted score.
+
+const MIN_IMPROVEMENT: f64 = 1e-9;
+// How many candidate moves are scored between two checks of the clock and the cancel flag.
+const TIME_CHECK_INTERVAL: usize = 256;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LocalSearchStrategy {
+    /// Apply the first improving move found and keep scanning from there.
+    First,
+    /// Scan the whole neighbourhood and apply the single best move.
+    Best,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LocalSearchOptions {
+    pub strategy: LocalSearchStrategy,
+    pub time_limit_ms: u64,
+    pub three_swap: bool,
+    /// Stops the search early once set; see cancelOptimization.
+    #[serde(skip)]
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Default for LocalSearchOptions {
+    fn default() -> Self {
+        LocalSearchOptions {
+            strategy: LocalSearchStrategy::First,
+            time_limit_ms: 2_000,
+            three_swap: false,
+            cancel: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LocalSearchStats {
+    pub two_swap_improvements: usize,
+    pub three_swap_improvements: usize,
+    pub evaluations: usize,
+    pub time: Duration,
+    pub timed_out: bool,
+    pub cancelled: bool,
+}
+
+impl LocalSearchStats {
+    pub fn improvements(&self) -> usize {
+        self.two_swap_improvements + self.three_swap_improvements
+    }
+}
+
+struct Budget<'o> {
+    start: Instant,
+    limit: Duration,
+    cancel: Option<&'o AtomicBool>,
+    evaluations: usize,
+    exhausted: bool,
+    cancelled: bool,
+}
+
+impl Budget<'_> {
+    #[inline(always)]
+    fn tick(&mut self) -> bool {
+        self.evaluations += 1;
+        if self.evaluations.is_multiple_of(TIME_CHECK_INTERVAL) {
+            self.cancelled = self.cancel.is_some_and(|c| c.load(Ordering::Relaxed));
+            self.exhausted = self.cancelled || self.start.elapsed() >= self.limit;
+        }
+        !self.exhausted
+    }
+}
+
+/// Runs local search over `free_seats` (seat ids of `scorer.graph`). Returns the total score
+/// gained and the statistics of the run.
+pub fn local_search(
+    arrangement: &mut SeatingArrangement,
+    free_seats: &[usize],
+    scorer: &Scorer,
+    options: &LocalSearchOptions,
+) -> (f64, LocalSearchStats) {
+    let mut budget = Budget {
+        start: Instant::now(),
+        limit: Duration::from_millis(options.time_limit_ms),
+        cancel: options.cancel.as_deref(),
+        evaluations: 0,
+        exhausted: false,
+        cancelled: false,
+    };
+    let mut stats = LocalSearchStats::default();
+    let mut gained = Ranked::default();
+
+    loop {
+        while let Some((delta, moves)) = two_swap_pass(arrangement, free_seats, scorer, options.strategy, &mut budget) {
+            gained += delta;
+            stats.two_swap_improvements += moves;
+        }
+        if !options.three_swap || budget.exhausted {
+            break;
+        }
+        // A successful 3-cycle usually opens up new pairwise swaps, so go back to 2-opt.
+        match three_swap_pass(arrangement, free_seats, scorer, options.strategy, &mut budget) {
+            Some((delta, moves)) => {
+                gained += delta;
+                stats.three_swap_improvements += moves;
+            }
+            None => break,
+        }
+    }
+
+    stats.evaluations = budget.evaluations;
+    stats.time = budget.start.elapsed();
+    stats.timed_out = budget.exhausted && !budget.cancelled;
+    stats.cancelled = budget.cancelled;
+    (gained.score, stats)
+}
+
+/// One scan of the pairwise swap neighbourhood. With `First`, every improving swap found during
+/// the scan is applied; with `Best`, only the best one. Returns the gain and the number of moves
+/// applied, or `None` if nothing improved.
+fn two_swap_pass(
+    arrangement: &mut SeatingArrangement,
+    free_seats: &[usize],
+    scorer: &Scorer,
+    strategy: LocalSearchStrategy,
+    budget: &mut Budget,
+) -> Option<(Ranked, usize)> {
+    let mut gained = Ranked::default();
+    let mut moves = 0;
+    let mut best: Option<(Ranked, usize, usize)> = None;
+    for i in 0..free_seats.len() {
+        for j in (i + 1)..free_seats.len() {
+            let (a, b) = (free_seats[i], free_seats[j]);
+            if scorer.graph.occupant(arrangement, a).is_none() && scorer.graph.occupant(arrangement, b).is_none() {
+                continue;
+            }
+            if !budget.tick() {
+                return finish(arrangement, scorer, gained, moves, best.map(|(d, a, b)| (d, vec![a, b])));
+            }
+            let delta = scorer.ranked_swap_delta(arrangement, a, b);
+            if !delta.beats(Ranked::default(), MIN_IMPROVEMENT) {
+                continue;
+            }
+            match strategy {
+                LocalSearchStrategy::First => {
+                    scorer.apply_cycle(arrangement, &[a, b]);
+                    gained += delta;
+                    moves += 1;
+                }
+                LocalSearchStrategy::Best => {
+                    if best.is_none_or(|(d, _, _)| delta.beats(d, 0.0)) {
+                        best = Some((delta, a, b));
+                    }
+                }
+            }
+        }
+    }
+    finish(arrangement, scorer, gained, moves, best.map(|(d, a, b)| (d, vec![a, b])))
+}
+
+/// One scan of the 3-cycle neighbourhood (both rotation directions of every triple). Stops at
+/// the first improving cycle with `First`, so that cheaper 2-opt gets another go.
+fn three_swap_pass(
+    arrangement: &mut SeatingArrangement,
+    free_seats: &[usize],
+    scorer: &Scorer,
+    strategy: LocalSearchStrategy,
+    budget: &mut Budget,
+) -> Option<(Ranked, usize)> {
+    let mut best: Option<(Ranked, Vec<usize>)> = None;
+    let n = free_seats.len();
+    for i in 0..n {
+        for j in (i + 1)..n {
+            for k in (j + 1)..n {
+                let (a, b, c) = (free_seats[i], free_seats[j], free_seats[k]);
+                let occupied = [a, b, c].iter().filter(|&&s| scorer.graph.occupant(arrangement, s).is_some()).count();
+                if occupied < 2 {
+                    continue;
+                }
+                for cycle in [[a, b, c], [a, c, b]].iter() {
+                    if !budget.tick() {
+                        return finish(arrangement, scorer, Ranked::default(), 0, best);
+                    }
+                    let delta = scorer.ranked_cycle_delta(arrangement, cycle);
+                    if !delta.beats(Ranked::default(), MIN_IMPROVEMENT) {
+                        continue;
+                    }
+                    match strategy {
+                        LocalSearchStrategy::First => {
+                            scorer.apply_cycle(arrangement, cycle);
+                            return Some((delta, 1));
+                        }
+                        LocalSearchStrategy::Best => {
+                            if best.as_ref().is_none_or(|(d, _)| delta.beats(*d, 0.0)) {
+                                best = Some((delta, cycle.to_vec()));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    finish(arrangement, scorer, Ranked::default(), 0, best)
+}
+
+fn finish(
+    arrangement: &mut SeatingArrangement,
+    scorer: &Scorer,
+    mut gained: Ranked,
+    mut moves: usize,
+    best: Option<(Ranked, Vec<usize>)>,
+) -> Option<(Ranked, usize)> {
+    if let Some((delta, cycle)) = best {
+        scorer.apply_cycle(arrangement, &cycle);
+        gained += delta;
+        moves += 1;
+    }
+    if moves > 0 { Some((gained, moves)) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::random_case;
+    use rand::prelude::*;
+
+    #[test]
+    fn local_search_never_lowers_the_score() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for bonus_config in ["none", "left", "right", "both"] {
+            for strategy in [LocalSearchStrategy::First, LocalSearchStrategy::Best] {
+                let (mut room, students_map) = random_case(&mut rng, 3, 4, bonus_config, 0.8);
+                let scorer = Scorer::new(&room, &students_map, 1.5, bonus_config);
+                let free_seats: Vec<usize> = (0..scorer.graph.coords.len()).collect();
+                let options = LocalSearchOptions { strategy, three_swap: true, ..LocalSearchOptions::default() };
+                let before = scorer.total_score(&room);
+                let (gained, _) = local_search(&mut room, &free_seats, &scorer, &options);
+                let after = scorer.total_score(&room);
+                assert!(gained >= 0.0);
+                assert!((after - before - gained).abs() < 1e-9, "{} {:?}", bonus_config, strategy);
+            }
+        }
+    }
+}
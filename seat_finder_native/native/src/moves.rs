@@ -0,0 +1,159 @@
+use crate::scoring::Scorer;
+use crate::{Coordinate, SeatingArrangement};
+use serde::Serialize;
+use std::collections::HashSet;
+
+// --- Manual Move Suggestions ---
+//
+// Scores single swaps, or moves into an empty seat, against the current arrangement with delta
+// scoring, and lists the students whose contribution changes. Used to preview manual swaps and
+// to point teachers at the best improvements still available after optimisation.
+
+const MIN_IMPROVEMENT: f64 = 1e-9;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentChange {
+    pub name: String,
+    pub moved: bool,
+    pub contribution_before: f64,
+    pub contribution_after: f64,
+    pub fulfilled_before: f64,
+    pub fulfilled_after: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveEvaluation {
+    pub from: Coordinate,
+    pub to: Coordinate,
+    /// `true` if one of the two seats is empty, so a single student moves.
+    pub into_empty_seat: bool,
+    pub delta: f64,
+    pub affected: Vec<StudentChange>,
+}
+
+/// Score change of swapping the occupants of seats `a` and `b`, and the students it affects.
+/// For a move into an empty seat, `from` is the occupied one. The arrangement is left unchanged.
+pub fn evaluate_move(arrangement: &mut SeatingArrangement, scorer: &Scorer, a: usize, b: usize) -> MoveEvaluation {
+    let (a, b) = if scorer.graph.occupant(arrangement, a).is_none() { (b, a) } else { (a, b) };
+    let mut seats: Vec<usize> = vec![a, b];
+    seats.extend_from_slice(scorer.graph.read_by(a));
+    seats.extend_from_slice(scorer.graph.read_by(b));
+    seats.sort_unstable();
+    seats.dedup();
+    let moved: HashSet<String> = [a, b].iter().filter_map(|&s| scorer.graph.occupant(arrangement, s).cloned()).collect();
+    let snapshot = |arrangement: &SeatingArrangement| -> Vec<(String, f64, f64)> {
+        seats
+            .iter()
+            .filter_map(|&s| {
+                scorer
+                    .graph
+                    .occupant(arrangement, s)
+                    .map(|name| (name.clone(), scorer.seat_score(arrangement, s), scorer.fulfilled(arrangement, s)))
+            })
+            .collect()
+    };
+
+    let delta = if moved.is_empty() { 0.0 } else { scorer.swap_delta(arrangement, a, b) };
+    let before = snapshot(arrangement);
+    scorer.apply_cycle(arrangement, &[a, b]);
+    let after = snapshot(arrangement);
+    scorer.undo_cycle(arrangement, &[a, b]);
+
+    let mut affected: Vec<StudentChange> = before
+        .into_iter()
+        .filter_map(|(name, contribution_before, fulfilled_before)| {
+            let &(_, contribution_after, fulfilled_after) = after.iter().find(|(n, _, _)| *n == name)?;
+            let moved = moved.contains(&name);
+            let changed = moved || contribution_before != contribution_after || fulfilled_before != fulfilled_after;
+            changed.then_some(StudentChange {
+                name,
+                moved,
+                contribution_before,
+                contribution_after,
+                fulfilled_before,
+                fulfilled_after,
+            })
+        })
+        .collect();
+    affected.sort_by(|x, y| x.name.cmp(&y.name));
+
+    MoveEvaluation {
+        from: scorer.graph.coords[a].clone(),
+        to: scorer.graph.coords[b].clone(),
+        into_empty_seat: moved.len() == 1,
+        delta,
+        affected,
+    }
+}
+
+/// The `k` swaps or moves into empty seats among `free_seats` that raise the score the most,
+/// best first. Each is scored on its own against the current arrangement.
+pub fn suggest_swaps(arrangement: &SeatingArrangement, scorer: &Scorer, free_seats: &[usize], k: usize) -> Vec<MoveEvaluation> {
+    let mut working = arrangement.clone();
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (i, &a) in free_seats.iter().enumerate() {
+        for &b in &free_seats[i + 1..] {
+            if scorer.graph.occupant(&working, a) == scorer.graph.occupant(&working, b) {
+                continue;
+            }
+            let delta = scorer.swap_delta(&mut working, a, b);
+            if delta > MIN_IMPROVEMENT {
+                candidates.push((delta, a, b));
+            }
+        }
+    }
+    candidates.sort_by(|x, y| y.0.total_cmp(&x.0));
+    candidates.truncate(k);
+    candidates.into_iter().map(|(_, a, b)| evaluate_move(&mut working, scorer, a, b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{arrangement, students};
+    use serde_json::json;
+
+    fn room() -> SeatingArrangement {
+        arrangement(json!({"tables": [{"top": ["A", "", ""], "bottom": ["", "", "B"], "bonus_left": null, "bonus_right": null}]}))
+    }
+
+    fn seat(scorer: &Scorer, section: &str, index: usize) -> usize {
+        scorer.graph.id_of(&Coordinate { table: 0, section: section.to_string(), index: Some(index) }).unwrap()
+    }
+
+    #[test]
+    fn moving_into_an_empty_seat_affects_the_mover_and_their_neighbours() {
+        let students_map = students(&[("A", &["B"]), ("B", &["A"])]);
+        let mut room = room();
+        let scorer = Scorer::new(&room, &students_map, 1.0, "none");
+        let (a, blank) = (seat(&scorer, "top", 0), seat(&scorer, "top", 2));
+        // The empty seat first: `from` is still the occupied one.
+        let evaluation = evaluate_move(&mut room, &scorer, blank, a);
+        assert!(evaluation.into_empty_seat);
+        assert_eq!(evaluation.from, scorer.graph.coords[a]);
+        let names: Vec<&str> = evaluation.affected.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["A", "B"]);
+        assert!(evaluation.affected[0].moved && !evaluation.affected[1].moved);
+        assert!(evaluation.delta > 0.0);
+    }
+
+    #[test]
+    fn suggestions_move_students_by_their_exact_delta() {
+        let students_map = students(&[("A", &["B"]), ("B", &["A"])]);
+        let room = room();
+        let scorer = Scorer::new(&room, &students_map, 1.0, "none");
+        let free_seats: Vec<usize> = (0..scorer.graph.coords.len()).collect();
+        let suggestions = suggest_swaps(&room, &scorer, &free_seats, 3);
+        assert!(!suggestions.is_empty());
+        for suggestion in &suggestions {
+            assert!(suggestion.into_empty_seat);
+            assert!(suggestion.affected.iter().all(|s| s.name == "A" || s.name == "B"));
+        }
+        let mut best = room.clone();
+        let (from, to) = (scorer.graph.id_of(&suggestions[0].from).unwrap(), scorer.graph.id_of(&suggestions[0].to).unwrap());
+        scorer.apply_cycle(&mut best, &[from, to]);
+        assert!((scorer.total_score(&best) - scorer.total_score(&room) - suggestions[0].delta).abs() < 1e-9);
+    }
+}
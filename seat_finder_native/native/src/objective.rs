@@ -0,0 +1,108 @@
+use crate::Student;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Sub};
+
+// --- Objective ---
+//
+// What the optimisers maximise. `sum` is the weighted wish score of evaluate_seating. The fairness
+// objectives pass each student's contribution through a concave utility before adding it up, so
+// a point for a student with little counts for more than a point for a student with a lot: the
+// search stops trading one student's only wish for another student's third. Gap penalties and
+// move costs stay linear. The utility is applied per seat, so delta scoring works unchanged.
+//
+// The lexicographic objective first maximises the number of students with at least one met wish
+// and only then the weighted score. Scores are compared as `Ranked` tuples; for the other
+// objectives the first element is always 0, so the comparison falls back to the score.
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Objective {
+    #[default]
+    Sum,
+    Sqrt,
+    Log1p,
+    Lexicographic,
+}
+
+impl Objective {
+    /// Utility of a student's contribution to the weighted score.
+    #[inline(always)]
+    pub fn utility(self, contribution: f64) -> f64 {
+        match self {
+            Objective::Sum | Objective::Lexicographic => contribution,
+            Objective::Sqrt => contribution.max(0.0).sqrt(),
+            Objective::Log1p => contribution.max(0.0).ln_1p(),
+        }
+    }
+
+    /// Best score `student` could reach on their own: three full-weight neighbours (sides and
+    /// across) and two diagonals, filled with wishes in that order. Gap penalties and move costs
+    /// only ever lower the score, so this stays an upper bound in minimal-disruption mode too.
+    pub fn best_student_score(self, student: &Student, bonus_parameter: f64) -> f64 {
+        let wishes = student.wishes.len();
+        let fulfilled = wishes.min(3) as f64 + wishes.saturating_sub(3).min(2) as f64 * 0.8;
+        self.utility(fulfilled * student.weight * bonus_parameter)
+    }
+
+    /// What one satisfied student is worth in `Ranked::weighted`: the best score any single
+    /// student can reach, so a rule only trades a satisfied student for at least that much. 0
+    /// unless the objective is lexicographic, where the count is always 0 anyway.
+    pub fn satisfied_weight(self, students_map: &HashMap<String, Student>, bonus_parameter: f64) -> f64 {
+        if self != Objective::Lexicographic {
+            return 0.0;
+        }
+        students_map.values().map(|s| self.best_student_score(s, bonus_parameter)).fold(0.0, f64::max)
+    }
+}
+
+/// A score as the objective compares it: first `satisfied` (students with at least one met wish,
+/// counted only by the lexicographic objective), then `score`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Ranked {
+    pub satisfied: i64,
+    pub score: f64,
+}
+
+impl Ranked {
+    /// Whether `self` is better than `other` by more than `min_improvement` in score, or has
+    /// more satisfied students.
+    #[inline(always)]
+    pub fn beats(self, other: Ranked, min_improvement: f64) -> bool {
+        self.satisfied > other.satisfied || (self.satisfied == other.satisfied && self.score > other.score + min_improvement)
+    }
+
+    /// Orders pairs as `beats` does, for sorting.
+    pub fn total_cmp(&self, other: &Ranked) -> Ordering {
+        self.satisfied.cmp(&other.satisfied).then(self.score.total_cmp(&other.score))
+    }
+
+    /// The pair as a single score, each satisfied student worth `satisfied_weight`. Acceptance
+    /// rules that weigh how much worse a move is use this, so that they can give up a satisfied
+    /// student, at a price, like any other loss.
+    #[inline(always)]
+    pub fn weighted(self, satisfied_weight: f64) -> f64 {
+        self.satisfied as f64 * satisfied_weight + self.score
+    }
+}
+
+impl Add for Ranked {
+    type Output = Ranked;
+    fn add(self, other: Ranked) -> Ranked {
+        Ranked { satisfied: self.satisfied + other.satisfied, score: self.score + other.score }
+    }
+}
+
+impl AddAssign for Ranked {
+    fn add_assign(&mut self, other: Ranked) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Ranked {
+    type Output = Ranked;
+    fn sub(self, other: Ranked) -> Ranked {
+        Ranked { satisfied: self.satisfied - other.satisfied, score: self.score - other.score }
+    }
+}
@@ -0,0 +1,176 @@
+use crate::diff::{diff_seating, SeatingDiff};
+use crate::local_search::{local_search, LocalSearchOptions, LocalSearchStrategy};
+use crate::scoring::Scorer;
+use crate::{build_free_coords, Coordinate, SeatingArrangement, Student};
+use serde::Serialize;
+use std::collections::HashMap;
+
+// --- Roster Changes ---
+//
+// Adds a late-joining student or removes one who left without re-running the optimiser. Only the
+// table that gains or loses a student is touched: after the change, its free seats get a short
+// local search, which also closes the gap a leaving student opens in a row. Everyone at other
+// tables and every pinned seat stays as it is. A new student's seat is picked by the delta score
+// of seating them there, so only one table is searched however many seats are empty.
+
+// Time cap for the local search on the affected table.
+const REPAIR_TIME_LIMIT_MS: u64 = 200;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterChange {
+    pub seating_arrangement: SeatingArrangement,
+    /// Where the added student was seated, or where the removed student sat.
+    pub seat: Coordinate,
+    pub diff: SeatingDiff,
+}
+
+/// Seats `name` (who must be in `students_map`) in the empty free seat where they add the most
+/// score, then re-optimises that seat's table.
+pub fn add_student(
+    arrangement: &SeatingArrangement,
+    fixed_coords: &[Coordinate],
+    students_map: &HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+    name: &str,
+) -> Result<RosterChange, String> {
+    if !students_map.contains_key(name) {
+        return Err(format!("Student {} is not in students_map", name));
+    }
+    let scorer = Scorer::new(arrangement, students_map, bonus_parameter, bonus_config);
+    if seat_of(&scorer, arrangement, name).is_some() {
+        return Err(format!("Student {} is already seated", name));
+    }
+    let free_seats = free_seat_ids(arrangement, fixed_coords, bonus_config, &scorer);
+
+    let mut new_arrangement = arrangement.clone();
+    let mut best: Option<(f64, usize)> = None;
+    for &seat in &free_seats {
+        if scorer.graph.occupant(&new_arrangement, seat).is_some() {
+            continue;
+        }
+        let before = scorer.local_score(&new_arrangement, &[seat]);
+        *scorer.graph.seat_mut(&mut new_arrangement, seat) = Some(name.to_string());
+        let gain = scorer.local_score(&new_arrangement, &[seat]) - before;
+        *scorer.graph.seat_mut(&mut new_arrangement, seat) = None;
+        if best.is_none_or(|(g, _)| gain > g) {
+            best = Some((gain, seat));
+        }
+    }
+    let (_, seat) = best.ok_or_else(|| "No empty seat for the new student".to_string())?;
+    *scorer.graph.seat_mut(&mut new_arrangement, seat) = Some(name.to_string());
+    let table_seats = table_free_seats(&scorer, &free_seats, scorer.graph.coords[seat].table);
+    local_search(&mut new_arrangement, &table_seats, &scorer, &repair_options());
+    // The local search may have moved the new student within the table.
+    let seat = seat_of(&scorer, &new_arrangement, name).unwrap();
+    Ok(finish(arrangement, new_arrangement, &scorer, seat, students_map, bonus_parameter, bonus_config))
+}
+
+/// Unseats `name` and re-optimises the free seats of the table they sat at.
+pub fn remove_student(
+    arrangement: &SeatingArrangement,
+    fixed_coords: &[Coordinate],
+    students_map: &HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+    name: &str,
+) -> Result<RosterChange, String> {
+    let scorer = Scorer::new(arrangement, students_map, bonus_parameter, bonus_config);
+    let seat = seat_of(&scorer, arrangement, name).ok_or_else(|| format!("Student {} is not seated", name))?;
+    let free_seats = free_seat_ids(arrangement, fixed_coords, bonus_config, &scorer);
+    let mut new_arrangement = arrangement.clone();
+    *scorer.graph.seat_mut(&mut new_arrangement, seat) = None;
+    let table_seats = table_free_seats(&scorer, &free_seats, scorer.graph.coords[seat].table);
+    local_search(&mut new_arrangement, &table_seats, &scorer, &repair_options());
+    Ok(finish(arrangement, new_arrangement, &scorer, seat, students_map, bonus_parameter, bonus_config))
+}
+
+fn seat_of(scorer: &Scorer, arrangement: &SeatingArrangement, name: &str) -> Option<usize> {
+    (0..scorer.graph.coords.len()).find(|&s| scorer.graph.occupant(arrangement, s).is_some_and(|n| n == name))
+}
+
+fn free_seat_ids(arrangement: &SeatingArrangement, fixed_coords: &[Coordinate], bonus_config: &str, scorer: &Scorer) -> Vec<usize> {
+    build_free_coords(arrangement, fixed_coords, bonus_config)
+        .iter()
+        .filter_map(|c| scorer.graph.id_of(c))
+        .collect()
+}
+
+fn table_free_seats(scorer: &Scorer, free_seats: &[usize], table: usize) -> Vec<usize> {
+    free_seats.iter().copied().filter(|&s| scorer.graph.coords[s].table == table).collect()
+}
+
+fn repair_options() -> LocalSearchOptions {
+    LocalSearchOptions {
+        strategy: LocalSearchStrategy::Best,
+        time_limit_ms: REPAIR_TIME_LIMIT_MS,
+        three_swap: true,
+        cancel: None,
+    }
+}
+
+fn finish(
+    before: &SeatingArrangement,
+    after: SeatingArrangement,
+    scorer: &Scorer,
+    seat: usize,
+    students_map: &HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+) -> RosterChange {
+    RosterChange {
+        diff: diff_seating(before, &after, students_map, bonus_parameter, bonus_config),
+        seating_arrangement: after,
+        seat: scorer.graph.coords[seat].clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{arrangement, students};
+    use serde_json::json;
+
+    // Empty seats as SeatingArrangement.createEmpty stores them.
+    fn room() -> SeatingArrangement {
+        arrangement(json!({"tables": [
+            {"top": ["A", "", ""], "bottom": ["", "", ""], "bonus_left": null, "bonus_right": null},
+            {"top": ["B", "", ""], "bottom": ["", "", ""], "bonus_left": null, "bonus_right": null},
+        ]}))
+    }
+
+    #[test]
+    fn add_student_sits_next_to_a_wished_student() {
+        let students_map = students(&[("A", &["C"]), ("B", &[]), ("C", &["A"])]);
+        let change = add_student(&room(), &[], &students_map, 1.0, "none", "C").unwrap();
+        assert_eq!(change.seat.table, 0);
+        let scorer = Scorer::new(&change.seating_arrangement, &students_map, 1.0, "none");
+        assert!(scorer.fulfilled(&change.seating_arrangement, seat_of(&scorer, &change.seating_arrangement, "C").unwrap()) > 0.0);
+    }
+
+    #[test]
+    fn add_student_only_rearranges_the_chosen_table() {
+        // B's wish pulls C to table 1; table 0, where A wishes for nobody seated, stays as it was.
+        let students_map = students(&[("A", &["Z"]), ("B", &["C"]), ("C", &[]), ("Z", &[])]);
+        let change = add_student(&room(), &[], &students_map, 1.0, "none", "C").unwrap();
+        assert_eq!(change.seat.table, 1);
+        assert_eq!(change.seating_arrangement.tables[0], room().tables[0]);
+    }
+
+    #[test]
+    fn add_student_rejects_seated_and_unknown_students() {
+        let students_map = students(&[("A", &[]), ("B", &[])]);
+        assert!(add_student(&room(), &[], &students_map, 1.0, "none", "A").is_err());
+        assert!(add_student(&room(), &[], &students_map, 1.0, "none", "Z").is_err());
+    }
+
+    #[test]
+    fn remove_student_empties_the_seat() {
+        let students_map = students(&[("A", &[]), ("B", &[])]);
+        let change = remove_student(&room(), &[], &students_map, 1.0, "none", "B").unwrap();
+        let scorer = Scorer::new(&change.seating_arrangement, &students_map, 1.0, "none");
+        assert!(seat_of(&scorer, &change.seating_arrangement, "B").is_none());
+        assert!(seat_of(&scorer, &change.seating_arrangement, "A").is_some());
+    }
+}
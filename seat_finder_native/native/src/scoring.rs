@@ -0,0 +1,475 @@
+use crate::disruption::DisruptionOptions;
+use crate::objective::{Objective, Ranked};
+use crate::{build_wishes_map, compute_gap_penalty, swap_seats, Coordinate, SeatingArrangement, Student};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+// --- Seat Graph ---
+//
+// A flat, precomputed view of the neighbour model used by `evaluate_seating`. Every seat gets
+// an id; for each seat we store the seats its occupant is scored against (`reads`) and the
+// seats whose occupants are scored against it (`read_by`). The relation is not symmetric:
+// a bonus seat reads the row ends, but the row ends do not read the bonus seat.
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AdjacencyKind {
+    Side,
+    Across,
+    Diagonal,
+    End,
+}
+
+impl AdjacencyKind {
+    #[inline(always)]
+    pub fn weight(self) -> f64 {
+        match self {
+            AdjacencyKind::Diagonal => 0.8,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Row {
+    Top,
+    Bottom,
+}
+
+pub struct SeatGraph {
+    pub coords: Vec<Coordinate>,
+    reads: Vec<Vec<(usize, AdjacencyKind)>>,
+    read_by: Vec<Vec<usize>>,
+    rows: Vec<Option<Row>>,
+    tables: Vec<Vec<usize>>,
+    ids: HashMap<Coordinate, usize>,
+}
+
+impl SeatGraph {
+    pub fn new(arrangement: &SeatingArrangement, bonus_config: &str) -> Self {
+        let mut coords = Vec::new();
+        let mut rows = Vec::new();
+        let mut ids = HashMap::new();
+        let mut push = |coord: Coordinate, row: Option<Row>, coords: &mut Vec<Coordinate>| {
+            ids.insert(coord.clone(), coords.len());
+            coords.push(coord);
+            rows.push(row);
+        };
+        for (t_idx, table) in arrangement.tables.iter().enumerate() {
+            for i in 0..table.top.len() {
+                push(Coordinate { table: t_idx, section: "top".to_string(), index: Some(i) }, Some(Row::Top), &mut coords);
+            }
+            for i in 0..table.bottom.len() {
+                push(Coordinate { table: t_idx, section: "bottom".to_string(), index: Some(i) }, Some(Row::Bottom), &mut coords);
+            }
+            if bonus_config == "left" || bonus_config == "both" {
+                push(Coordinate { table: t_idx, section: "bonus_left".to_string(), index: None }, None, &mut coords);
+            }
+            if bonus_config == "right" || bonus_config == "both" {
+                push(Coordinate { table: t_idx, section: "bonus_right".to_string(), index: None }, None, &mut coords);
+            }
+        }
+
+        let seat = |table: usize, section: &str, index: Option<usize>| {
+            ids.get(&Coordinate { table, section: section.to_string(), index }).copied()
+        };
+        let mut reads = Vec::with_capacity(coords.len());
+        for coord in &coords {
+            let t = coord.table;
+            let mut list = Vec::new();
+            let mut add = |id: Option<usize>, kind: AdjacencyKind| {
+                if let Some(id) = id {
+                    list.push((id, kind));
+                }
+            };
+            match (coord.section.as_str(), coord.index) {
+                ("top", Some(i)) | ("bottom", Some(i)) => {
+                    let (own, other) = if coord.section == "top" { ("top", "bottom") } else { ("bottom", "top") };
+                    if i > 0 {
+                        add(seat(t, own, Some(i - 1)), AdjacencyKind::Side);
+                    }
+                    add(seat(t, own, Some(i + 1)), AdjacencyKind::Side);
+                    add(seat(t, other, Some(i)), AdjacencyKind::Across);
+                    if i > 0 {
+                        add(seat(t, other, Some(i - 1)), AdjacencyKind::Diagonal);
+                    }
+                    add(seat(t, other, Some(i + 1)), AdjacencyKind::Diagonal);
+                }
+                ("bonus_left", None) => {
+                    add(seat(t, "top", Some(0)), AdjacencyKind::End);
+                    add(seat(t, "bottom", Some(0)), AdjacencyKind::End);
+                }
+                ("bonus_right", None) => {
+                    let last = arrangement.tables[t].top.len().saturating_sub(1);
+                    add(seat(t, "top", Some(last)), AdjacencyKind::End);
+                    add(seat(t, "bottom", Some(last)), AdjacencyKind::End);
+                }
+                _ => {}
+            }
+            reads.push(list);
+        }
+
+        let mut read_by = vec![Vec::new(); coords.len()];
+        for (id, list) in reads.iter().enumerate() {
+            for &(neighbor, _) in list {
+                read_by[neighbor].push(id);
+            }
+        }
+        let mut tables = vec![Vec::new(); arrangement.tables.len()];
+        for (id, coord) in coords.iter().enumerate() {
+            tables[coord.table].push(id);
+        }
+        SeatGraph { coords, reads, read_by, rows, tables, ids }
+    }
+
+    pub fn id_of(&self, coord: &Coordinate) -> Option<usize> {
+        self.ids.get(coord).copied()
+    }
+
+    /// All seats of table `table`.
+    pub fn table_seats(&self, table: usize) -> &[usize] {
+        &self.tables[table]
+    }
+
+    /// Seats the occupant of `seat` is scored against, with the kind of each adjacency.
+    pub fn reads(&self, seat: usize) -> &[(usize, AdjacencyKind)] {
+        &self.reads[seat]
+    }
+
+    /// Seats whose occupant's score depends on who sits in `seat`.
+    pub fn read_by(&self, seat: usize) -> &[usize] {
+        &self.read_by[seat]
+    }
+
+    pub fn seat_mut<'b>(&self, arrangement: &'b mut SeatingArrangement, seat: usize) -> &'b mut Option<String> {
+        let coord = &self.coords[seat];
+        let table = &mut arrangement.tables[coord.table];
+        match coord.section.as_str() {
+            "top" => &mut table.top[coord.index.unwrap()],
+            "bottom" => &mut table.bottom[coord.index.unwrap()],
+            "bonus_left" => &mut table.bonus_left,
+            _ => &mut table.bonus_right,
+        }
+    }
+
+    #[inline(always)]
+    pub fn occupant<'b>(&self, arrangement: &'b SeatingArrangement, seat: usize) -> Option<&'b String> {
+        let coord = &self.coords[seat];
+        let table = &arrangement.tables[coord.table];
+        match coord.section.as_str() {
+            "top" => table.top[coord.index.unwrap()].as_ref(),
+            "bottom" => table.bottom[coord.index.unwrap()].as_ref(),
+            "bonus_left" => table.bonus_left.as_ref(),
+            "bonus_right" => table.bonus_right.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Number of students seated in `a` who sit somewhere else (or nowhere) in `b`.
+    pub fn distance(&self, a: &SeatingArrangement, b: &SeatingArrangement) -> usize {
+        let seats_in_b: HashMap<&String, usize> =
+            (0..self.coords.len()).filter_map(|s| self.occupant(b, s).map(|name| (name, s))).collect();
+        (0..self.coords.len())
+            .filter(|&s| self.occupant(a, s).is_some_and(|name| seats_in_b.get(name) != Some(&s)))
+            .count()
+    }
+}
+
+// --- Delta Scorer ---
+//
+// Scores seats and moves against a SeatGraph without cloning tables. The sum of `seat_score`
+// over all seats plus the row gap penalties equals `evaluate_seating`, minus the move cost of
+// every student away from their home seat when minimal-disruption mode is on. With a fairness
+// objective, every student's contribution goes through its utility first.
+pub struct Scorer<'a> {
+    pub graph: SeatGraph,
+    students_map: &'a HashMap<String, Student>,
+    wishes_map: HashMap<&'a str, HashSet<&'a str>>,
+    bonus_parameter: f64,
+    // Occupant of every seat in the arrangement being re-optimised; empty when not in use.
+    home: Vec<Option<String>>,
+    move_cost: f64,
+    objective: Objective,
+}
+
+pub const GAP_PENALTY: f64 = 100.0;
+
+impl<'a> Scorer<'a> {
+    pub fn new(
+        arrangement: &SeatingArrangement,
+        students_map: &'a HashMap<String, Student>,
+        bonus_parameter: f64,
+        bonus_config: &str,
+    ) -> Self {
+        Scorer {
+            graph: SeatGraph::new(arrangement, bonus_config),
+            students_map,
+            wishes_map: build_wishes_map(students_map),
+            bonus_parameter,
+            home: Vec::new(),
+            move_cost: 0.0,
+            objective: Objective::Sum,
+        }
+    }
+
+    /// Charges `move_cost` for every student not in their seat in `options.home`, if set.
+    pub fn with_disruption(mut self, options: &DisruptionOptions) -> Self {
+        if let Some(home) = &options.home {
+            self.home = (0..self.graph.coords.len()).map(|s| self.graph.occupant(home, s).cloned()).collect();
+            self.move_cost = options.move_cost;
+        }
+        self
+    }
+
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Whether the occupant of `seat` sits somewhere other than their home seat.
+    #[inline(always)]
+    pub fn moved(&self, arrangement: &SeatingArrangement, seat: usize) -> bool {
+        !self.home.is_empty()
+            && self.graph.occupant(arrangement, seat).is_some_and(|name| self.home[seat].as_ref() != Some(name))
+    }
+
+    /// Home seat of every student seated in the home arrangement.
+    pub fn home_seats(&self) -> HashMap<&str, usize> {
+        self.home.iter().enumerate().filter_map(|(s, name)| name.as_ref().map(|n| (n.as_str(), s))).collect()
+    }
+
+    /// Weighted number of wishes met for the occupant of `seat` (diagonals count 0.8).
+    #[inline(always)]
+    pub fn fulfilled(&self, arrangement: &SeatingArrangement, seat: usize) -> f64 {
+        let name = match self.graph.occupant(arrangement, seat) {
+            Some(name) => name,
+            None => return 0.0,
+        };
+        let wishes = match self.wishes_map.get(name.as_str()) {
+            Some(wishes) => wishes,
+            None => return 0.0,
+        };
+        let mut fulfilled = 0.0;
+        for &(neighbor, kind) in self.graph.reads(seat) {
+            if let Some(other) = self.graph.occupant(arrangement, neighbor) {
+                if wishes.contains(other.as_str()) {
+                    fulfilled += kind.weight();
+                }
+            }
+        }
+        fulfilled
+    }
+
+    /// Contribution of the occupant of `seat` to the total score.
+    #[inline(always)]
+    pub fn seat_score(&self, arrangement: &SeatingArrangement, seat: usize) -> f64 {
+        let move_penalty = if self.moved(arrangement, seat) { self.move_cost } else { 0.0 };
+        let student = match self.graph.occupant(arrangement, seat).and_then(|n| self.students_map.get(n)) {
+            Some(student) => student,
+            None => return -move_penalty,
+        };
+        let fulfilled = self.fulfilled(arrangement, seat);
+        let base_score = fulfilled * student.weight;
+        let contribution = if fulfilled > 0.0 { base_score * self.bonus_parameter } else { base_score };
+        self.objective.utility(contribution) - move_penalty
+    }
+
+    /// Score of a single table, as `evaluate_table` would compute it.
+    pub fn table_score(&self, arrangement: &SeatingArrangement, table: usize) -> f64 {
+        let mut score: f64 = self.graph.table_seats(table).iter().map(|&s| self.seat_score(arrangement, s)).sum();
+        let table = &arrangement.tables[table];
+        score += compute_gap_penalty(&table.top, GAP_PENALTY);
+        score += compute_gap_penalty(&table.bottom, GAP_PENALTY);
+        score
+    }
+
+    /// Score of the whole arrangement under the scorer's objective and move costs.
+    pub fn total_score(&self, arrangement: &SeatingArrangement) -> f64 {
+        (0..arrangement.tables.len()).fold(0.0, |sum, t| sum + self.table_score(arrangement, t))
+    }
+
+    /// `total_score` together with the number of satisfied students, for the lexicographic
+    /// objective.
+    pub fn total_ranked(&self, arrangement: &SeatingArrangement) -> Ranked {
+        let satisfied = (0..self.graph.coords.len()).filter(|&s| self.satisfied(arrangement, s)).count();
+        Ranked { satisfied: satisfied as i64, score: self.total_score(arrangement) }
+    }
+
+    /// Seated students with at least one wish: the most students `total_ranked` can count as
+    /// satisfied.
+    pub fn wishing(&self, arrangement: &SeatingArrangement) -> i64 {
+        let wishing = (0..self.graph.coords.len())
+            .filter_map(|s| self.graph.occupant(arrangement, s))
+            .filter(|name| self.wishes_map.get(name.as_str()).is_some_and(|wishes| !wishes.is_empty()))
+            .count();
+        wishing as i64
+    }
+
+    /// Whether the occupant of `seat` has a wish met. Always false unless the objective is
+    /// lexicographic, so that other objectives compare on score alone.
+    #[inline(always)]
+    fn satisfied(&self, arrangement: &SeatingArrangement, seat: usize) -> bool {
+        self.objective == Objective::Lexicographic && self.fulfilled(arrangement, seat) > 0.0
+    }
+
+    /// `local_score` together with the number of satisfied students among the affected seats.
+    pub fn local_ranked(&self, arrangement: &SeatingArrangement, seats: &[usize]) -> Ranked {
+        let score = self.local_score(arrangement, seats);
+        if self.objective != Objective::Lexicographic {
+            return Ranked { satisfied: 0, score };
+        }
+        let mut affected: Vec<usize> = Vec::with_capacity(seats.len() * 6);
+        for &seat in seats {
+            affected.push(seat);
+            affected.extend_from_slice(self.graph.read_by(seat));
+        }
+        affected.sort_unstable();
+        affected.dedup();
+        let satisfied = affected.iter().filter(|&&s| self.satisfied(arrangement, s)).count();
+        Ranked { satisfied: satisfied as i64, score }
+    }
+
+    /// Score of every seat and row that a move touching `seats` can change.
+    pub fn local_score(&self, arrangement: &SeatingArrangement, seats: &[usize]) -> f64 {
+        let mut affected: Vec<usize> = Vec::with_capacity(seats.len() * 6);
+        let mut rows: Vec<(usize, Row)> = Vec::with_capacity(seats.len());
+        for &seat in seats {
+            affected.push(seat);
+            affected.extend_from_slice(self.graph.read_by(seat));
+            if let Some(row) = self.graph.rows[seat] {
+                let key = (self.graph.coords[seat].table, row);
+                if !rows.contains(&key) {
+                    rows.push(key);
+                }
+            }
+        }
+        affected.sort_unstable();
+        affected.dedup();
+        let mut score: f64 = affected.iter().map(|&s| self.seat_score(arrangement, s)).sum();
+        for &(table, row) in &rows {
+            let table = &arrangement.tables[table];
+            score += match row {
+                Row::Top => compute_gap_penalty(&table.top, GAP_PENALTY),
+                Row::Bottom => compute_gap_penalty(&table.bottom, GAP_PENALTY),
+            };
+        }
+        score
+    }
+
+    /// Moves the occupant of `cycle[0]` to `cycle[1]`, `cycle[1]` to `cycle[2]`, ... and the last
+    /// back to `cycle[0]`. A cycle of two seats is a plain swap.
+    pub fn apply_cycle(&self, arrangement: &mut SeatingArrangement, cycle: &[usize]) {
+        for k in (1..cycle.len()).rev() {
+            swap_seats(arrangement, &self.graph.coords[cycle[0]], &self.graph.coords[cycle[k]]);
+        }
+    }
+
+    pub fn undo_cycle(&self, arrangement: &mut SeatingArrangement, cycle: &[usize]) {
+        for k in 1..cycle.len() {
+            swap_seats(arrangement, &self.graph.coords[cycle[0]], &self.graph.coords[cycle[k]]);
+        }
+    }
+
+    /// Score change of applying `apply_cycle(cycle)`. The arrangement is left unchanged.
+    pub fn cycle_delta(&self, arrangement: &mut SeatingArrangement, cycle: &[usize]) -> f64 {
+        let before = self.local_score(arrangement, cycle);
+        self.apply_cycle(arrangement, cycle);
+        let after = self.local_score(arrangement, cycle);
+        self.undo_cycle(arrangement, cycle);
+        after - before
+    }
+
+    /// `cycle_delta` as a `Ranked` change.
+    pub fn ranked_cycle_delta(&self, arrangement: &mut SeatingArrangement, cycle: &[usize]) -> Ranked {
+        let before = self.local_ranked(arrangement, cycle);
+        self.apply_cycle(arrangement, cycle);
+        let after = self.local_ranked(arrangement, cycle);
+        self.undo_cycle(arrangement, cycle);
+        after - before
+    }
+
+    /// `swap_delta` as a `Ranked` change.
+    #[inline(always)]
+    pub fn ranked_swap_delta(&self, arrangement: &mut SeatingArrangement, a: usize, b: usize) -> Ranked {
+        if self.graph.occupant(arrangement, a) == self.graph.occupant(arrangement, b) {
+            return Ranked::default();
+        }
+        self.ranked_cycle_delta(arrangement, &[a, b])
+    }
+
+    /// Score change of swapping the occupants of `a` and `b`. The arrangement is left unchanged.
+    #[inline(always)]
+    pub fn swap_delta(&self, arrangement: &mut SeatingArrangement, a: usize, b: usize) -> f64 {
+        if self.graph.occupant(arrangement, a) == self.graph.occupant(arrangement, b) {
+            return 0.0;
+        }
+        self.cycle_delta(arrangement, &[a, b])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{arrangement, random_case, students};
+    use crate::{evaluate_seating, is_perfect_seating};
+    use rand::prelude::*;
+    use serde_json::json;
+
+    fn room(top: [&str; 3], bottom: [&str; 3]) -> SeatingArrangement {
+        arrangement(json!({"tables": [{"top": top, "bottom": bottom, "bonus_left": "", "bonus_right": null}]}))
+    }
+
+    #[test]
+    fn distance_counts_students_who_sit_elsewhere() {
+        let a = room(["A", "", ""], ["", "B", ""]);
+        let graph = SeatGraph::new(&a, "left");
+        assert_eq!(graph.distance(&a, &a), 0);
+        assert_eq!(graph.distance(&a, &room(["", "A", ""], ["", "B", ""])), 1);
+        assert_eq!(graph.distance(&a, &room(["B", "", ""], ["", "A", ""])), 2);
+    }
+
+    #[test]
+    fn distance_counts_students_missing_from_the_other_arrangement() {
+        let a = room(["A", "C", ""], ["", "B", ""]);
+        let graph = SeatGraph::new(&a, "left");
+        assert_eq!(graph.distance(&a, &room(["A", "", ""], ["", "B", ""])), 1);
+        assert_eq!(graph.distance(&room(["A", "", ""], ["", "B", ""]), &a), 0);
+    }
+
+    #[test]
+    fn deltas_match_evaluate_seating() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for bonus_config in ["none", "left", "right", "both"] {
+            for _ in 0..20 {
+                let (mut room, students_map) = random_case(&mut rng, 3, 4, bonus_config, 0.8);
+                let wishes_map = build_wishes_map(&students_map);
+                let scorer = Scorer::new(&room, &students_map, 1.5, bonus_config);
+                let evaluate = |room: &SeatingArrangement| evaluate_seating(room, &students_map, &wishes_map, 1.5, bonus_config);
+                let before = evaluate(&room);
+                assert!((scorer.total_score(&room) - before).abs() < 1e-9);
+
+                let seats = scorer.graph.coords.len();
+                let (a, b) = (rng.gen_range(0..seats), rng.gen_range(0..seats));
+                let delta = scorer.swap_delta(&mut room, a, b);
+                scorer.apply_cycle(&mut room, &[a, b]);
+                assert!((evaluate(&room) - before - delta).abs() < 1e-9, "{} swap {} {}", bonus_config, a, b);
+
+                let cycle: Vec<usize> = (0..seats).choose_multiple(&mut rng, 3);
+                let before = evaluate(&room);
+                let delta = scorer.cycle_delta(&mut room, &cycle);
+                scorer.apply_cycle(&mut room, &cycle);
+                assert!((evaluate(&room) - before - delta).abs() < 1e-9, "{} cycle {:?}", bonus_config, cycle);
+            }
+        }
+    }
+
+    #[test]
+    fn diagonal_wishes_satisfy_under_the_lexicographic_objective() {
+        let room = room(["A", "", ""], ["", "B", ""]);
+        let students_map = students(&[("A", &["B"]), ("B", &["A"]), ("C", &["A"])]);
+        let scorer = Scorer::new(&room, &students_map, 1.0, "left").with_objective(Objective::Lexicographic);
+        assert_eq!(scorer.wishing(&room), 2);
+        assert_eq!(scorer.total_ranked(&room).satisfied, 2);
+        assert!(!is_perfect_seating(&room, &students_map, &build_wishes_map(&students_map)));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::explain::{explain_seating, TableExplanation};
+use crate::{SeatingArrangement, Student};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// --- Seating Statistics ---
+//
+// Wish fulfilment figures for the result page, built on explain_seating so they count exactly
+// the neighbours the optimiser scores. A wish is met when the wished student sits in one of those
+// seats, diagonals included; the weighted figures count diagonals at their adjacency weight.
+// Fairness metrics look at the spread of the fulfilment percentages rather than their sum.
+
+const HISTOGRAM_BIN_WIDTH: f64 = 20.0;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StatisticsConfig {
+    pub bonus_parameter: f64,
+    pub bonus_config: String,
+}
+
+impl Default for StatisticsConfig {
+    fn default() -> Self {
+        StatisticsConfig { bonus_parameter: 1.0, bonus_config: "none".to_string() }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentStatistics {
+    pub name: String,
+    pub table: usize,
+    pub wishes: usize,
+    pub met: usize,
+    /// `None` for students without wishes.
+    pub percentage: Option<f64>,
+    /// Met wishes counted with their adjacency weight (diagonals 0.8).
+    pub weighted_met: f64,
+    pub contribution: f64,
+}
+
+/// Students with a fulfilment percentage above `from` and at most `to`. The first bin holds
+/// exactly 0%.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBin {
+    pub from: f64,
+    pub to: f64,
+    pub count: usize,
+}
+
+/// How many students of one weight class have wishes, and how many of those got none met.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeightClassZeros {
+    pub weight: f64,
+    pub students: usize,
+    pub zero: usize,
+}
+
+/// Spread of the fulfilment percentages over students with wishes; `None` if nobody has any.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FairnessMetrics {
+    pub min_satisfaction: Option<f64>,
+    /// Nearest-rank 10th percentile.
+    pub p10_satisfaction: Option<f64>,
+    /// 0 when everyone has the same percentage, approaching 1 as it concentrates on few students.
+    pub gini: Option<f64>,
+    pub zero_by_weight: Vec<WeightClassZeros>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatingStatistics {
+    /// Seated students, in seat order.
+    pub students: Vec<StudentStatistics>,
+    /// Students with wishes of which none is met.
+    pub none_fulfilled: Vec<String>,
+    /// Mean number of met wishes over students with wishes; `None` if nobody has any.
+    pub average_fulfilled: Option<f64>,
+    pub average_percentage: Option<f64>,
+    pub histogram: Vec<HistogramBin>,
+    pub fairness: FairnessMetrics,
+    pub tables: Vec<TableExplanation>,
+    pub total_score: f64,
+}
+
+pub fn compute_statistics(
+    arrangement: &SeatingArrangement,
+    students_map: &HashMap<String, Student>,
+    config: &StatisticsConfig,
+) -> SeatingStatistics {
+    let explanation = explain_seating(arrangement, students_map, config.bonus_parameter, &config.bonus_config);
+    let students: Vec<StudentStatistics> = explanation
+        .students
+        .into_iter()
+        .map(|s| {
+            let met = s.met_wishes.len();
+            let wishes = met + s.unmet_wishes.len();
+            StudentStatistics {
+                name: s.name,
+                table: s.seat.table,
+                wishes,
+                met,
+                percentage: (wishes > 0).then(|| met as f64 * 100.0 / wishes as f64),
+                weighted_met: s.fulfilled,
+                contribution: s.contribution,
+            }
+        })
+        .collect();
+
+    let with_wishes: Vec<&StudentStatistics> = students.iter().filter(|s| s.wishes > 0).collect();
+    let none_fulfilled = with_wishes.iter().filter(|s| s.met == 0).map(|s| s.name.clone()).collect();
+    let count = with_wishes.len() as f64;
+    let average_fulfilled = (count > 0.0).then(|| with_wishes.iter().fold(0.0, |sum, s| sum + s.met as f64) / count);
+    let average_percentage =
+        (count > 0.0).then(|| with_wishes.iter().fold(0.0, |sum, s| sum + s.percentage.unwrap_or(0.0)) / count);
+
+    let mut histogram = vec![HistogramBin { from: 0.0, to: 0.0, count: 0 }];
+    let mut from = 0.0;
+    while from < 100.0 {
+        histogram.push(HistogramBin { from, to: from + HISTOGRAM_BIN_WIDTH, count: 0 });
+        from += HISTOGRAM_BIN_WIDTH;
+    }
+    for percentage in with_wishes.iter().filter_map(|s| s.percentage) {
+        let bin = if percentage <= 0.0 { 0 } else { ((percentage / HISTOGRAM_BIN_WIDTH).ceil() as usize).min(histogram.len() - 1) };
+        histogram[bin].count += 1;
+    }
+    let fairness = fairness(&with_wishes, students_map);
+
+    SeatingStatistics {
+        students,
+        none_fulfilled,
+        average_fulfilled,
+        average_percentage,
+        histogram,
+        fairness,
+        tables: explanation.tables,
+        total_score: explanation.total_score,
+    }
+}
+
+/// The fairness metrics of `arrangement` on their own. They do not depend on `bonusParameter`.
+pub fn fairness_of(
+    arrangement: &SeatingArrangement,
+    students_map: &HashMap<String, Student>,
+    bonus_config: &str,
+) -> FairnessMetrics {
+    let config = StatisticsConfig { bonus_config: bonus_config.to_string(), ..StatisticsConfig::default() };
+    compute_statistics(arrangement, students_map, &config).fairness
+}
+
+fn fairness(with_wishes: &[&StudentStatistics], students_map: &HashMap<String, Student>) -> FairnessMetrics {
+    let mut percentages: Vec<f64> = with_wishes.iter().filter_map(|s| s.percentage).collect();
+    percentages.sort_by(|a, b| a.total_cmp(b));
+    let n = percentages.len();
+    let mean = percentages.iter().fold(0.0, |sum, p| sum + p) / n.max(1) as f64;
+    // Mean absolute difference over all pairs, halved and divided by the mean. On sorted values
+    // the pairwise sum is sum((2i - n + 1) * x_i).
+    let gini = (n > 0).then(|| {
+        if mean <= 0.0 {
+            return 0.0;
+        }
+        let weighted = percentages.iter().enumerate().fold(0.0, |sum, (i, p)| sum + (2.0 * i as f64 - n as f64 + 1.0) * p);
+        weighted / (n as f64 * n as f64 * mean)
+    });
+
+    let mut zero_by_weight: Vec<WeightClassZeros> = Vec::new();
+    for student in with_wishes {
+        let weight = students_map.get(&student.name).map_or(1.0, |s| s.weight);
+        let class = match zero_by_weight.iter_mut().find(|c| c.weight == weight) {
+            Some(class) => class,
+            None => {
+                zero_by_weight.push(WeightClassZeros { weight, students: 0, zero: 0 });
+                zero_by_weight.last_mut().unwrap()
+            }
+        };
+        class.students += 1;
+        class.zero += (student.met == 0) as usize;
+    }
+    zero_by_weight.sort_by(|a, b| a.weight.total_cmp(&b.weight));
+
+    FairnessMetrics {
+        min_satisfaction: percentages.first().copied(),
+        p10_satisfaction: (n > 0).then(|| percentages[((n as f64 * 0.1).ceil() as usize).max(1) - 1]),
+        gini,
+        zero_by_weight,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::students;
+    use rand::prelude::*;
+
+    fn with_percentages(percentages: &[f64]) -> Vec<StudentStatistics> {
+        percentages
+            .iter()
+            .enumerate()
+            .map(|(i, &percentage)| StudentStatistics {
+                name: format!("S{}", i),
+                table: 0,
+                wishes: 4,
+                met: (percentage / 25.0) as usize,
+                percentage: Some(percentage),
+                weighted_met: 0.0,
+                contribution: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gini_matches_the_pairwise_definition() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for n in 1..30 {
+            let percentages: Vec<f64> = (0..n).map(|_| rng.gen_range(0..5) as f64 * 25.0).collect();
+            let statistics = with_percentages(&percentages);
+            let refs: Vec<&StudentStatistics> = statistics.iter().collect();
+            let gini = fairness(&refs, &HashMap::new()).gini.unwrap();
+            let mean = percentages.iter().sum::<f64>() / n as f64;
+            let pairs: f64 = percentages.iter().flat_map(|a| percentages.iter().map(move |b| (a - b).abs())).sum();
+            let expected = if mean > 0.0 { pairs / (2.0 * (n * n) as f64 * mean) } else { 0.0 };
+            assert!((gini - expected).abs() < 1e-9, "{:?}", percentages);
+        }
+    }
+
+    #[test]
+    fn p10_is_the_nearest_rank() {
+        let p10 = |n: usize| {
+            let percentages: Vec<f64> = (0..n).rev().map(|i| i as f64).collect();
+            let statistics = with_percentages(&percentages);
+            let refs: Vec<&StudentStatistics> = statistics.iter().collect();
+            fairness(&refs, &HashMap::new()).p10_satisfaction
+        };
+        assert_eq!(p10(0), None);
+        assert_eq!(p10(1), Some(0.0));
+        assert_eq!(p10(10), Some(0.0));
+        assert_eq!(p10(11), Some(1.0));
+        assert_eq!(p10(25), Some(2.0));
+    }
+
+    #[test]
+    fn zero_met_students_by_weight_class() {
+        let statistics = with_percentages(&[0.0, 50.0, 0.0]);
+        let refs: Vec<&StudentStatistics> = statistics.iter().collect();
+        let mut students_map = students(&[("S0", &[]), ("S1", &[]), ("S2", &[])]);
+        students_map.get_mut("S2").unwrap().weight = 2.0;
+        let metrics = fairness(&refs, &students_map);
+        let classes: Vec<(f64, usize, usize)> = metrics.zero_by_weight.iter().map(|c| (c.weight, c.students, c.zero)).collect();
+        assert_eq!(classes, [(1.0, 2, 1), (2.0, 1, 1)]);
+        assert_eq!(metrics.min_satisfaction, Some(0.0));
+    }
+}
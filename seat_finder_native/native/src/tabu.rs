@@ -0,0 +1,209 @@
+use crate::acceptance::AcceptanceStats;
+use crate::disruption;
+use crate::objective::Ranked;
+use crate::scoring::Scorer;
+use crate::trace::Trace;
+use crate::{
+    build_free_coords, build_wishes_map, is_best_possible, is_cancelled, local_search_phase, max_satisfied,
+    report_progress, report_snapshot, Coordinate, OptimizeOptions, PerformanceLog, SeatingArrangement, Student,
+};
+use rand::prelude::*;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Instant;
+
+// --- Tabu Search ---
+//
+// Each step samples `candidates` random swaps of free seats and applies the best one, even if it
+// makes the arrangement worse. Students who just moved are tabu for `tenure` steps, so the search
+// cannot immediately swap them back and is pushed out of local optima instead. A tabu move is
+// still allowed if it would give a new best score (aspiration). Swaps are compared as `Ranked`
+// pairs, so under the lexicographic objective the number of satisfied students comes first.
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TabuOptions {
+    pub candidates: usize,
+    pub tenure: usize,
+}
+
+impl Default for TabuOptions {
+    fn default() -> Self {
+        TabuOptions {
+            candidates: 100,
+            tenure: 7,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn optimize_seating_tabu(
+    initial_arrangement: SeatingArrangement,
+    fixed_coords: Vec<Coordinate>,
+    students_map: HashMap<String, Student>,
+    bonus_parameter: f64,
+    bonus_config: &str,
+    iterations: usize,
+    early_stop: bool,
+    options: &OptimizeOptions,
+    run_id: usize,
+) -> (SeatingArrangement, PerformanceLog) {
+    let start = Instant::now();
+    let candidates = options.tabu.candidates.max(1);
+    // Every step scores `candidates` swaps, so this keeps the same evaluation budget as annealing.
+    let steps = iterations / candidates;
+    let mut current_arrangement = initial_arrangement.clone();
+    let mut best_arrangement = initial_arrangement;
+    let wishes_map = build_wishes_map(&students_map);
+    let scorer = Scorer::new(&current_arrangement, &students_map, bonus_parameter, bonus_config)
+        .with_disruption(&options.disruption)
+        .with_objective(options.objective);
+    let mut current = scorer.total_ranked(&current_arrangement);
+    let mut best = current;
+    let max_satisfied = max_satisfied(&scorer, &current_arrangement, options.objective);
+    // Students away from their home seat, for the minimal-disruption move cap.
+    let mut moved = disruption::count_moved(&current_arrangement, &scorer);
+    let mut log_messages = Vec::new();
+
+    let free_coords = build_free_coords(&current_arrangement, &fixed_coords, bonus_config);
+    let free_seats: Vec<usize> = free_coords.iter().filter_map(|c| scorer.graph.id_of(c)).collect();
+    // Student name -> first step at which they may move again.
+    let mut tabu_until: HashMap<String, usize> = HashMap::new();
+    let is_tabu = |tabu_until: &HashMap<String, usize>, name: Option<&String>, step: usize| {
+        name.and_then(|n| tabu_until.get(n)).is_some_and(|&until| step < until)
+    };
+
+    let mut acceptance = AcceptanceStats::default();
+    let mut trace = Trace::new(&options.trace);
+
+    let mut rng = thread_rng();
+    let mut steps_done = 0;
+    let mut early_stop_iteration = None;
+    for step in 0..steps {
+        if free_seats.len() < 2 {
+            break;
+        }
+        if is_cancelled(options) {
+            log_messages.push(format!("Run {}: Cancelled at tabu step {}", run_id, step));
+            break;
+        }
+        steps_done = step + 1;
+        if trace.due(step) {
+            trace.record(step * candidates, best.score, current.score, 0.0, &acceptance);
+        }
+        if step % 200 == 0 {
+            report_progress(options, step * candidates, best.score, 0.0);
+            report_snapshot(options, &best_arrangement, best.score);
+        }
+
+        let mut best_move: Option<(Ranked, usize, usize, isize)> = None;
+        for _ in 0..candidates {
+            let a = free_seats[rng.gen_range(0..free_seats.len())];
+            let b = free_seats[rng.gen_range(0..free_seats.len())];
+            let (name_a, name_b) = (scorer.graph.occupant(&current_arrangement, a), scorer.graph.occupant(&current_arrangement, b));
+            if a == b || name_a == name_b {
+                continue;
+            }
+            let tabu = is_tabu(&tabu_until, name_a, step) || is_tabu(&tabu_until, name_b, step);
+            let moved_delta = match options.disruption.max_moves {
+                Some(max) => {
+                    let moved_delta = disruption::swap_moved_delta(&mut current_arrangement, &scorer, a, b);
+                    if moved as isize + moved_delta > max as isize {
+                        continue;
+                    }
+                    moved_delta
+                }
+                None => 0,
+            };
+            let delta = scorer.ranked_swap_delta(&mut current_arrangement, a, b);
+            if tabu && !(current + delta).beats(best, 0.0) {
+                continue;
+            }
+            if best_move.is_none_or(|(d, _, _, _)| delta.beats(d, 0.0)) {
+                best_move = Some((delta, a, b, moved_delta));
+            }
+        }
+
+        let (delta, a, b, moved_delta) = match best_move {
+            Some(m) => m,
+            None => {
+                acceptance.rejected += 1;
+                continue;
+            }
+        };
+        if delta.total_cmp(&Ranked::default()) != Ordering::Less {
+            acceptance.improving += 1;
+        } else {
+            acceptance.accepted_worse += 1;
+        }
+        scorer.apply_cycle(&mut current_arrangement, &[a, b]);
+        current += delta;
+        moved = (moved as isize + moved_delta) as usize;
+        for seat in [a, b].iter() {
+            if let Some(name) = scorer.graph.occupant(&current_arrangement, *seat) {
+                tabu_until.insert(name.clone(), step + 1 + options.tabu.tenure);
+            }
+        }
+        if current.beats(best, 0.0) {
+            best_arrangement = current_arrangement.clone();
+            best = current;
+            if early_stop && is_best_possible(&best_arrangement, best.satisfied, max_satisfied, &students_map, &wishes_map) {
+                log_messages.push(format!("Run {}: Early stopping at tabu step {}", run_id, step));
+                early_stop_iteration = Some(step);
+                break;
+            }
+        }
+    }
+    let trace = trace.finish(steps_done * candidates, best.score, current.score, 0.0, &acceptance);
+
+    // Local search phase.
+    let (_, local_search_stats) = local_search_phase(
+        &mut best_arrangement,
+        &free_coords,
+        &students_map,
+        bonus_parameter,
+        bonus_config,
+        options,
+        run_id,
+        &mut log_messages,
+    );
+    let optimization_time = start.elapsed();
+    log_messages.push(format!("Run {}: Tabu search ran {} steps", run_id, steps_done));
+    log_messages.push(format!("Run {}: Total optimization time: {:?}", run_id, optimization_time));
+    let log_summary = log_messages.join("\n");
+    let perf_log = PerformanceLog {
+        run_id,
+        total_iterations: steps_done,
+        optimization_time,
+        local_search: local_search_stats,
+        acceptance,
+        trace,
+        early_stop_iteration,
+        moves_evaluated: steps_done * candidates,
+        log_summary,
+    };
+    (best_arrangement, perf_log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objective::Objective;
+    use crate::test_util::split_pair;
+
+    // The best score the search itself tracked, before the local search phase.
+    fn tracked_best(objective: Objective) -> f64 {
+        let (room, students_map) = split_pair();
+        let options = OptimizeOptions { objective, tabu: TabuOptions { candidates: 20, tenure: 2 }, ..OptimizeOptions::default() };
+        let (_, log) = optimize_seating_tabu(room, Vec::new(), students_map, 1.0, "none", 400, false, &options, 0);
+        log.trace.last().unwrap().best_score
+    }
+
+    #[test]
+    fn best_follows_the_objective() {
+        assert_eq!(tracked_best(Objective::Sum), 10.0);
+        // Splitting H and X satisfies two students instead of one, so it wins at a score of 2.
+        assert_eq!(tracked_best(Objective::Lexicographic), 2.0);
+    }
+}
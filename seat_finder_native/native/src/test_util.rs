@@ -0,0 +1,84 @@
+use crate::{SeatingArrangement, Student, Table};
+use rand::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+// --- Test Fixtures ---
+//
+// Arrangements are written as JSON, the way the routes send them, so the tests go through the
+// same deserialisation (including "" for empty seats) as the addon.
+
+pub fn arrangement(value: Value) -> SeatingArrangement {
+    serde_json::from_value(value).unwrap()
+}
+
+/// A students map from `(name, wishes)` pairs, every student with weight 1.
+pub fn students(wishes: &[(&str, &[&str])]) -> HashMap<String, Student> {
+    wishes
+        .iter()
+        .map(|(name, wishes)| {
+            let student = Student { name: name.to_string(), wishes: wishes.iter().map(|w| w.to_string()).collect(), weight: 1.0 };
+            (name.to_string(), student)
+        })
+        .collect()
+}
+
+/// `tables` tables with `seats_per_row` seats per row and the bonus seats of `bonus_config`,
+/// a `fill` share of them taken by students with up to four random wishes and random weights.
+pub fn random_case(
+    rng: &mut StdRng,
+    tables: usize,
+    seats_per_row: usize,
+    bonus_config: &str,
+    fill: f64,
+) -> (SeatingArrangement, HashMap<String, Student>) {
+    let left = bonus_config == "left" || bonus_config == "both";
+    let right = bonus_config == "right" || bonus_config == "both";
+    let mut arrangement = SeatingArrangement {
+        tables: (0..tables)
+            .map(|_| Table { top: vec![None; seats_per_row], bottom: vec![None; seats_per_row], bonus_left: None, bonus_right: None })
+            .collect(),
+    };
+    let mut seats: Vec<(usize, usize, usize)> = Vec::new();
+    for t in 0..tables {
+        for i in 0..seats_per_row {
+            seats.push((t, 0, i));
+            seats.push((t, 1, i));
+        }
+        if left {
+            seats.push((t, 2, 0));
+        }
+        if right {
+            seats.push((t, 3, 0));
+        }
+    }
+    seats.shuffle(rng);
+    let count = (seats.len() as f64 * fill) as usize;
+    let names: Vec<String> = (0..count).map(|i| format!("S{}", i)).collect();
+    let mut students_map = HashMap::new();
+    for name in &names {
+        let wishes = (0..rng.gen_range(0..5)).map(|_| names[rng.gen_range(0..count)].clone()).collect();
+        students_map.insert(name.clone(), Student { name: name.clone(), wishes, weight: rng.gen_range(1.0..3.0) });
+    }
+    for (name, &(t, section, i)) in names.iter().zip(seats.iter()) {
+        let table = &mut arrangement.tables[t];
+        let seat = match section {
+            0 => &mut table.top[i],
+            1 => &mut table.bottom[i],
+            2 => &mut table.bonus_left,
+            _ => &mut table.bonus_right,
+        };
+        *seat = Some(name.clone());
+    }
+    (arrangement, students_map)
+}
+
+/// Two tables of two facing seats, seated H-X and Y-Z. H (weight 10) wishes for X, Y for H and Z
+/// for X: H-X scores 10 but satisfies only H, while H-Y and X-Z score 2 and satisfy Y and Z.
+pub fn split_pair() -> (SeatingArrangement, HashMap<String, Student>) {
+    let pair = |top: &str, bottom: &str| json!({"top": [top], "bottom": [bottom], "bonus_left": null, "bonus_right": null});
+    let room = arrangement(json!({"tables": [pair("H", "X"), pair("Y", "Z")]}));
+    let mut students_map = students(&[("H", &["X"]), ("X", &[]), ("Y", &["H"]), ("Z", &["X"])]);
+    students_map.get_mut("H").unwrap().weight = 10.0;
+    (room, students_map)
+}
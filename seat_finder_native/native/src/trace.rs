@@ -0,0 +1,108 @@
+use crate::acceptance::AcceptanceStats;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+// --- Convergence Trace ---
+//
+// Each run samples its best score, current score, temperature and acceptance rate at a fixed
+// iteration interval. The trace keeps at most `max_points` points: once it is full, every other
+// point is dropped and the interval doubles, so the points always cover the whole run evenly no
+// matter how long it turns out to be.
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TraceOptions {
+    /// 0 turns the trace off.
+    pub max_points: usize,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        TraceOptions { max_points: 200 }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracePoint {
+    pub iteration: usize,
+    pub elapsed_ms: f64,
+    pub best_score: f64,
+    pub current_score: f64,
+    pub temperature: f64,
+    /// Share of moves accepted since the previous point; `None` if the algorithm has no
+    /// accept/reject step or nothing was evaluated.
+    pub acceptance_rate: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct Trace {
+    started: Instant,
+    max_points: usize,
+    interval: usize,
+    points: Vec<TracePoint>,
+    // Acceptance counts at the previous point.
+    accepted: usize,
+    evaluated: usize,
+}
+
+impl Trace {
+    pub fn new(options: &TraceOptions) -> Self {
+        Trace {
+            started: Instant::now(),
+            max_points: options.max_points,
+            interval: 1,
+            points: Vec::new(),
+            accepted: 0,
+            evaluated: 0,
+        }
+    }
+
+    /// Whether `iteration` should be recorded.
+    #[inline(always)]
+    pub fn due(&self, iteration: usize) -> bool {
+        self.max_points > 0 && iteration.is_multiple_of(self.interval)
+    }
+
+    pub fn record(&mut self, iteration: usize, best_score: f64, current_score: f64, temperature: f64, stats: &AcceptanceStats) {
+        if self.max_points == 0 {
+            return;
+        }
+        let point = self.point(iteration, best_score, current_score, temperature, stats);
+        self.points.push(point);
+        if self.points.len() > self.max_points {
+            let mut i = 0;
+            self.points.retain(|_| {
+                i += 1;
+                i % 2 == 1
+            });
+            self.interval *= 2;
+        }
+    }
+
+    fn point(&mut self, iteration: usize, best_score: f64, current_score: f64, temperature: f64, stats: &AcceptanceStats) -> TracePoint {
+        let accepted = stats.improving + stats.accepted_worse;
+        let evaluated = accepted + stats.rejected;
+        let window = evaluated - self.evaluated;
+        let acceptance_rate = (window > 0).then(|| (accepted - self.accepted) as f64 / window as f64);
+        self.accepted = accepted;
+        self.evaluated = evaluated;
+        TracePoint {
+            iteration,
+            elapsed_ms: self.started.elapsed().as_secs_f64() * 1000.0,
+            best_score,
+            current_score,
+            temperature,
+            acceptance_rate,
+        }
+    }
+
+    /// The recorded points, with `iteration` as the final one unless it was already recorded.
+    pub fn finish(mut self, iteration: usize, best_score: f64, current_score: f64, temperature: f64, stats: &AcceptanceStats) -> Vec<TracePoint> {
+        if self.max_points > 0 && self.points.last().is_none_or(|p| p.iteration != iteration) {
+            let point = self.point(iteration, best_score, current_score, temperature, stats);
+            self.points.push(point);
+        }
+        self.points
+    }
+}
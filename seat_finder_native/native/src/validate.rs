@@ -0,0 +1,214 @@
+use crate::{Coordinate, SeatingArrangement, Student};
+use serde::Serialize;
+use std::collections::HashMap;
+
+// --- Input Validation ---
+//
+// The optimisers trust their input: a student seated twice is scored twice, a student missing
+// from the students map scores nothing, and a coordinate outside the arrangement panics a worker
+// thread. validate lists everything of that kind up front so a job can be rejected before any
+// thread starts.
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProblemKind {
+    /// A name seated more than once.
+    DuplicateName,
+    /// A seated name missing from the students map.
+    UnknownStudent,
+    /// A fixed coordinate naming a table or row index that does not exist.
+    OutOfRange,
+    /// A section name that does not exist, an index on a bonus seat or none on a row seat, or a
+    /// bonus seat the bonus configuration does not have.
+    WrongSection,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Problem {
+    pub kind: ProblemKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The seats involved: every seat of a duplicate name, otherwise the one offending seat.
+    pub coords: Vec<Coordinate>,
+}
+
+pub fn validate(
+    arrangement: &SeatingArrangement,
+    fixed_coords: &[Coordinate],
+    students_map: &HashMap<String, Student>,
+    bonus_config: &str,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let has_left = bonus_config == "left" || bonus_config == "both";
+    let has_right = bonus_config == "right" || bonus_config == "both";
+
+    // Seats in the order SeatGraph numbers them, so problems come out in seat order.
+    let mut seated: Vec<(&String, Coordinate)> = Vec::new();
+    for (t, table) in arrangement.tables.iter().enumerate() {
+        let rows = [("top", &table.top), ("bottom", &table.bottom)];
+        for (section, row) in rows.iter() {
+            for (i, seat) in row.iter().enumerate() {
+                if let Some(name) = seat {
+                    seated.push((name, Coordinate { table: t, section: section.to_string(), index: Some(i) }));
+                }
+            }
+        }
+        let bonus = [("bonus_left", &table.bonus_left, has_left), ("bonus_right", &table.bonus_right, has_right)];
+        for (section, seat, present) in bonus.iter() {
+            if let Some(name) = seat {
+                let coord = Coordinate { table: t, section: section.to_string(), index: None };
+                if !present {
+                    problems.push(Problem {
+                        kind: ProblemKind::WrongSection,
+                        message: format!("{} sits in {} of table {}, which bonus configuration \"{}\" does not have", name, section, t, bonus_config),
+                        name: Some(name.clone()),
+                        coords: vec![coord],
+                    });
+                    continue;
+                }
+                seated.push((name, coord));
+            }
+        }
+    }
+
+    let mut seats_of: Vec<(&String, Vec<Coordinate>)> = Vec::new();
+    for (name, coord) in &seated {
+        match seats_of.iter_mut().find(|(n, _)| n == name) {
+            Some((_, coords)) => coords.push(coord.clone()),
+            None => seats_of.push((name, vec![coord.clone()])),
+        }
+    }
+    for (name, coords) in seats_of {
+        if coords.len() > 1 {
+            problems.push(Problem {
+                kind: ProblemKind::DuplicateName,
+                message: format!("{} is seated {} times", name, coords.len()),
+                name: Some(name.clone()),
+                coords: coords.clone(),
+            });
+        }
+        if !students_map.contains_key(name) {
+            problems.push(Problem {
+                kind: ProblemKind::UnknownStudent,
+                message: format!("{} is seated but not in the students map", name),
+                name: Some(name.clone()),
+                coords,
+            });
+        }
+    }
+
+    for coord in fixed_coords {
+        let problem = |kind: ProblemKind, message: String| Problem { kind, message, name: None, coords: vec![coord.clone()] };
+        let table = match arrangement.tables.get(coord.table) {
+            Some(table) => table,
+            None => {
+                problems.push(problem(
+                    ProblemKind::OutOfRange,
+                    format!("fixed seat on table {}, but there are only {} tables", coord.table, arrangement.tables.len()),
+                ));
+                continue;
+            }
+        };
+        match (coord.section.as_str(), coord.index) {
+            ("top", Some(i)) | ("bottom", Some(i)) => {
+                let len = if coord.section == "top" { table.top.len() } else { table.bottom.len() };
+                if i >= len {
+                    problems.push(problem(
+                        ProblemKind::OutOfRange,
+                        format!("fixed seat {} {} of table {}, but the row has {} seats", coord.section, i, coord.table, len),
+                    ));
+                }
+            }
+            ("top", None) | ("bottom", None) => problems.push(problem(
+                ProblemKind::WrongSection,
+                format!("fixed seat in {} of table {} has no index", coord.section, coord.table),
+            )),
+            ("bonus_left", None) | ("bonus_right", None) => {
+                let present = if coord.section == "bonus_left" { has_left } else { has_right };
+                if !present {
+                    problems.push(problem(
+                        ProblemKind::WrongSection,
+                        format!("fixed seat in {} of table {}, which bonus configuration \"{}\" does not have", coord.section, coord.table, bonus_config),
+                    ));
+                }
+            }
+            ("bonus_left", Some(_)) | ("bonus_right", Some(_)) => problems.push(problem(
+                ProblemKind::WrongSection,
+                format!("fixed seat in {} of table {} has an index, but bonus seats have none", coord.section, coord.table),
+            )),
+            (section, _) => problems.push(problem(ProblemKind::WrongSection, format!("fixed seat in unknown section \"{}\"", section))),
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{arrangement, students};
+    use serde_json::json;
+
+    fn room() -> SeatingArrangement {
+        arrangement(json!({"tables": [
+            {"top": ["A", "", "B"], "bottom": ["", "A", ""], "bonus_left": "", "bonus_right": null},
+            {"top": ["", "", ""], "bottom": ["", "", ""], "bonus_left": null, "bonus_right": "C"}
+        ]}))
+    }
+
+    fn coord(table: usize, section: &str, index: Option<usize>) -> Coordinate {
+        Coordinate { table, section: section.to_string(), index }
+    }
+
+    fn kinds(problems: &[Problem]) -> Vec<ProblemKind> {
+        problems.iter().map(|p| p.kind).collect()
+    }
+
+    #[test]
+    fn valid_input_has_no_problems() {
+        let room = arrangement(json!({"tables": [{"top": ["A", ""], "bottom": ["", "B"], "bonus_left": "C", "bonus_right": null}]}));
+        let fixed = [coord(0, "top", Some(1)), coord(0, "bonus_left", None)];
+        assert!(validate(&room, &fixed, &students(&[("A", &[]), ("B", &[]), ("C", &[])]), "left").is_empty());
+    }
+
+    #[test]
+    fn duplicate_name_lists_every_seat() {
+        let problems = validate(&room(), &[], &students(&[("A", &[]), ("B", &[]), ("C", &[])]), "both");
+        assert_eq!(kinds(&problems), [ProblemKind::DuplicateName]);
+        assert_eq!(problems[0].name.as_deref(), Some("A"));
+        assert_eq!(problems[0].coords, [coord(0, "top", Some(0)), coord(0, "bottom", Some(1))]);
+    }
+
+    #[test]
+    fn unknown_student_is_reported_once() {
+        let problems = validate(&room(), &[], &students(&[("B", &[]), ("C", &[])]), "both");
+        assert_eq!(kinds(&problems), [ProblemKind::DuplicateName, ProblemKind::UnknownStudent]);
+        assert_eq!(problems[1].name.as_deref(), Some("A"));
+        assert_eq!(problems[1].coords.len(), 2);
+    }
+
+    #[test]
+    fn out_of_range_fixed_seats() {
+        let fixed = [coord(2, "top", Some(0)), coord(0, "bottom", Some(3))];
+        let problems = validate(&room(), &fixed, &students(&[("A", &[]), ("B", &[]), ("C", &[])]), "both");
+        assert_eq!(kinds(&problems[1..]), [ProblemKind::OutOfRange, ProblemKind::OutOfRange]);
+        assert_eq!(problems[2].coords, [coord(0, "bottom", Some(3))]);
+    }
+
+    #[test]
+    fn wrong_sections() {
+        let fixed = [
+            coord(0, "top", None),
+            coord(0, "bonus_left", Some(0)),
+            coord(0, "bonus_left", None),
+            coord(0, "middle", Some(0)),
+        ];
+        let problems = validate(&room(), &fixed, &students(&[("A", &[]), ("B", &[]), ("C", &[])]), "right");
+        assert_eq!(kinds(&problems[1..]), [ProblemKind::WrongSection; 4]);
+        // C sits in a bonus seat the configuration lacks.
+        let problems = validate(&room(), &[], &students(&[("A", &[]), ("B", &[]), ("C", &[])]), "left");
+        assert_eq!(kinds(&problems), [ProblemKind::WrongSection, ProblemKind::DuplicateName]);
+        assert_eq!(problems[0].coords, [coord(1, "bonus_right", None)]);
+    }
+}
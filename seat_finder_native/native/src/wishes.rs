@@ -0,0 +1,287 @@
+use crate::Student;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// --- Wish Analysis ---
+//
+// Checks a students map before it is optimised. Wishes naming unknown students, the student
+// themselves or the same student twice are reported and left out of everything else. The
+// remaining wishes form a graph: components are built from wishes in either direction, cliques
+// from mutual wishes only, since a group that all wish for each other wants one table. A seat
+// has at most 5 neighbours (2 for a bonus seat), so a student with more wishes can never have
+// all of them met.
+
+/// Most neighbours a row seat can have: two beside, one across and two diagonally across.
+const MAX_NEIGHBOURS: usize = 5;
+/// Neighbours of a bonus seat: the two row ends.
+const MAX_BONUS_NEIGHBOURS: usize = 2;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WishAnalysisConfig {
+    /// Seats per table including bonus seats; cliques are only checked when set.
+    pub seats_per_table: Option<usize>,
+    pub bonus_config: String,
+}
+
+impl Default for WishAnalysisConfig {
+    fn default() -> Self {
+        WishAnalysisConfig { seats_per_table: None, bonus_config: "none".to_string() }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WishRef {
+    pub student: String,
+    pub wish: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsatisfiableStudent {
+    pub name: String,
+    pub wishes: usize,
+    pub max_neighbours: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WishAnalysis {
+    pub students: usize,
+    /// Wishes left after dropping unknown, self and duplicate wishes.
+    pub valid_wishes: usize,
+    pub unknown_wishes: Vec<WishRef>,
+    pub self_wishes: Vec<String>,
+    /// One entry per repetition.
+    pub duplicate_wishes: Vec<WishRef>,
+    /// Students nobody wished for.
+    pub unwished: Vec<String>,
+    /// Share of valid wishes that are returned; `None` without any.
+    pub reciprocity: Option<f64>,
+    /// Largest first; isolated students are components of their own.
+    pub components: Vec<Vec<String>>,
+    /// Maximal groups of mutual wishes with more members than a table has seats.
+    pub oversized_cliques: Vec<Vec<String>>,
+    /// Students with more wishes than any seat has neighbours.
+    pub unsatisfiable: Vec<UnsatisfiableStudent>,
+    /// Students who could have all wishes met, but not in a bonus seat. Empty without bonus seats.
+    pub bonus_limited: Vec<String>,
+}
+
+pub fn analyze_wishes(students_map: &HashMap<String, Student>, config: &WishAnalysisConfig) -> WishAnalysis {
+    let mut names: Vec<&String> = students_map.keys().collect();
+    names.sort();
+    let index: HashMap<&str, usize> = names.iter().enumerate().map(|(i, name)| (name.as_str(), i)).collect();
+    let n = names.len();
+
+    let mut unknown_wishes = Vec::new();
+    let mut self_wishes = Vec::new();
+    let mut duplicate_wishes = Vec::new();
+    let mut wished: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, name) in names.iter().enumerate() {
+        for wish in &students_map[*name].wishes {
+            match index.get(wish.as_str()) {
+                None => unknown_wishes.push(WishRef { student: name.to_string(), wish: wish.clone() }),
+                Some(&j) if j == i => {
+                    if !self_wishes.contains(*name) {
+                        self_wishes.push(name.to_string());
+                    }
+                }
+                Some(&j) if wished[i].contains(&j) => {
+                    duplicate_wishes.push(WishRef { student: name.to_string(), wish: wish.clone() })
+                }
+                Some(&j) => wished[i].push(j),
+            }
+        }
+    }
+    let wish_sets: Vec<HashSet<usize>> = wished.iter().map(|w| w.iter().copied().collect()).collect();
+
+    let valid_wishes: usize = wished.iter().map(|w| w.len()).sum();
+    let returned = (0..n).flat_map(|i| wished[i].iter().map(move |&j| (i, j))).filter(|&(i, j)| wish_sets[j].contains(&i)).count();
+    let reciprocity = (valid_wishes > 0).then(|| returned as f64 / valid_wishes as f64);
+
+    let mut is_wished = vec![false; n];
+    for &j in wished.iter().flatten() {
+        is_wished[j] = true;
+    }
+    let unwished = (0..n).filter(|&i| !is_wished[i]).map(|i| names[i].clone()).collect();
+
+    let mut undirected: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+    let mut mutual: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+    for i in 0..n {
+        for &j in &wished[i] {
+            undirected[i].insert(j);
+            undirected[j].insert(i);
+            if wish_sets[j].contains(&i) {
+                mutual[i].insert(j);
+            }
+        }
+    }
+
+    let mut components = Vec::new();
+    let mut seen = vec![false; n];
+    for start in 0..n {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut component = vec![start];
+        let mut next = 0;
+        while next < component.len() {
+            let mut neighbours: Vec<usize> = undirected[component[next]].iter().copied().filter(|&j| !seen[j]).collect();
+            neighbours.sort_unstable();
+            for j in neighbours {
+                seen[j] = true;
+                component.push(j);
+            }
+            next += 1;
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+
+    let mut oversized_cliques = Vec::new();
+    if let Some(seats) = config.seats_per_table {
+        let mut cliques = Vec::new();
+        bron_kerbosch(&mutual, Vec::new(), (0..n).collect(), Vec::new(), &mut cliques);
+        cliques.retain(|c| c.len() > seats);
+        for clique in &mut cliques {
+            clique.sort_unstable();
+        }
+        cliques.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        oversized_cliques = cliques;
+    }
+
+    let has_bonus = config.bonus_config != "none";
+    let mut unsatisfiable = Vec::new();
+    let mut bonus_limited = Vec::new();
+    for i in 0..n {
+        let wishes = wished[i].len();
+        if wishes > MAX_NEIGHBOURS {
+            unsatisfiable.push(UnsatisfiableStudent { name: names[i].clone(), wishes, max_neighbours: MAX_NEIGHBOURS });
+        } else if has_bonus && wishes > MAX_BONUS_NEIGHBOURS {
+            bonus_limited.push(names[i].clone());
+        }
+    }
+
+    let to_names = |groups: Vec<Vec<usize>>| -> Vec<Vec<String>> {
+        groups.into_iter().map(|g| g.into_iter().map(|i| names[i].clone()).collect()).collect()
+    };
+    WishAnalysis {
+        students: n,
+        valid_wishes,
+        unknown_wishes,
+        self_wishes,
+        duplicate_wishes,
+        unwished,
+        reciprocity,
+        components: to_names(components),
+        oversized_cliques: to_names(oversized_cliques),
+        unsatisfiable,
+        bonus_limited,
+    }
+}
+
+/// Maximal cliques of `graph`, with pivoting. `current` is the clique being grown, `candidates`
+/// may still join it and `excluded` have already been tried.
+fn bron_kerbosch(
+    graph: &[HashSet<usize>],
+    current: Vec<usize>,
+    mut candidates: Vec<usize>,
+    mut excluded: Vec<usize>,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() {
+            cliques.push(current);
+        }
+        return;
+    }
+    let pivot = candidates
+        .iter()
+        .chain(excluded.iter())
+        .copied()
+        .max_by_key(|&u| candidates.iter().filter(|v| graph[u].contains(v)).count())
+        .unwrap();
+    let branches: Vec<usize> = candidates.iter().copied().filter(|v| !graph[pivot].contains(v)).collect();
+    for v in branches {
+        let mut next = current.clone();
+        next.push(v);
+        let next_candidates = candidates.iter().copied().filter(|u| graph[v].contains(u)).collect();
+        let next_excluded = excluded.iter().copied().filter(|u| graph[v].contains(u)).collect();
+        bron_kerbosch(graph, next, next_candidates, next_excluded, cliques);
+        candidates.retain(|&u| u != v);
+        excluded.push(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::students;
+
+    fn config(seats_per_table: Option<usize>, bonus_config: &str) -> WishAnalysisConfig {
+        WishAnalysisConfig { seats_per_table, bonus_config: bonus_config.to_string() }
+    }
+
+    #[test]
+    fn invalid_wishes_are_reported_and_dropped() {
+        let students_map = students(&[("A", &["B", "A", "B", "X"]), ("B", &["A"]), ("C", &["A", "A"])]);
+        let analysis = analyze_wishes(&students_map, &WishAnalysisConfig::default());
+        assert_eq!(analysis.unknown_wishes, [WishRef { student: "A".to_string(), wish: "X".to_string() }]);
+        assert_eq!(analysis.self_wishes, ["A"]);
+        assert_eq!(
+            analysis.duplicate_wishes,
+            [
+                WishRef { student: "A".to_string(), wish: "B".to_string() },
+                WishRef { student: "C".to_string(), wish: "A".to_string() }
+            ]
+        );
+        assert_eq!(analysis.valid_wishes, 3);
+        assert_eq!(analysis.reciprocity, Some(2.0 / 3.0));
+        assert_eq!(analysis.unwished, ["C"]);
+    }
+
+    #[test]
+    fn components_follow_wishes_in_either_direction() {
+        let students_map = students(&[("A", &["B"]), ("B", &[]), ("C", &["B"]), ("D", &[]), ("E", &["D"])]);
+        let analysis = analyze_wishes(&students_map, &WishAnalysisConfig::default());
+        assert_eq!(analysis.components, [vec!["A", "B", "C"], vec!["D", "E"]]);
+        assert_eq!(analysis.reciprocity, Some(0.0));
+        assert_eq!(analyze_wishes(&students(&[("A", &[])]), &WishAnalysisConfig::default()).reciprocity, None);
+    }
+
+    #[test]
+    fn cliques_need_mutual_wishes_and_a_table_size() {
+        let students_map = students(&[
+            ("A", &["B", "C", "D"]),
+            ("B", &["A", "C", "D"]),
+            ("C", &["A", "B", "D"]),
+            ("D", &["A", "B", "C"]),
+            ("E", &["A", "B"]),
+        ]);
+        assert!(analyze_wishes(&students_map, &config(None, "none")).oversized_cliques.is_empty());
+        assert!(analyze_wishes(&students_map, &config(Some(4), "none")).oversized_cliques.is_empty());
+        assert_eq!(analyze_wishes(&students_map, &config(Some(3), "none")).oversized_cliques, [vec!["A", "B", "C", "D"]]);
+    }
+
+    #[test]
+    fn too_many_wishes() {
+        let students_map = students(&[
+            ("A", &["B", "C", "D", "E", "F", "G"]),
+            ("B", &["C", "D", "E"]),
+            ("C", &["D", "E"]),
+            ("D", &[]),
+            ("E", &[]),
+            ("F", &[]),
+            ("G", &[]),
+        ]);
+        let analysis = analyze_wishes(&students_map, &config(None, "none"));
+        assert_eq!(analysis.unsatisfiable.len(), 1);
+        assert_eq!((analysis.unsatisfiable[0].name.as_str(), analysis.unsatisfiable[0].wishes), ("A", 6));
+        assert!(analysis.bonus_limited.is_empty());
+        assert_eq!(analyze_wishes(&students_map, &config(None, "left")).bonus_limited, ["B"]);
+    }
+}